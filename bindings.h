@@ -1 +1,2 @@
 #include <uv.h>
+#include <poll.h>
@@ -0,0 +1,182 @@
+use std::{cell::RefCell, collections::VecDeque, mem::take, net::SocketAddr, rc::Rc};
+
+use crate::{
+    http::{ClientCodec, Request, Response},
+    uv::{
+        Completion, ConnectRequest, Errno, FramedStream, IHandle, Loop, StreamHandle, completion,
+    },
+};
+
+// type
+
+pub struct ResponseCallback<'a>(pub Box<dyn FnMut(Result<Response, Errno>) + 'a>);
+
+struct ClientState {
+    framed: FramedStream<ClientCodec>,
+    host: String,
+    connected: bool,
+    closed: bool,
+    // requests sent before the connection came up, written in order once it does
+    queued: Vec<Request>,
+    pending: VecDeque<ResponseCallback<'static>>,
+}
+
+// NOTE: one keep-alive connection, requests are pipelined and answered in order
+#[derive(Clone)]
+pub struct HttpClient {
+    state: Rc<RefCell<ClientState>>,
+}
+
+// impl
+
+impl HttpClient {
+    pub fn connect(r#loop: &Loop, addr: &SocketAddr) -> Result<Self, Errno> {
+        let mut tcp = r#loop.new_tcp()?;
+        let client = Self {
+            state: Rc::new(RefCell::new(ClientState {
+                framed: FramedStream::new(tcp, ClientCodec::new()),
+                host: addr.to_string(),
+                connected: false,
+                closed: false,
+                queued: Vec::new(),
+                pending: VecDeque::new(),
+            })),
+        };
+
+        let on_connect = client.clone();
+        if let Err(err) = tcp.connect(
+            ConnectRequest::new(),
+            addr,
+            move |_: ConnectRequest, status: Result<(), Errno>| on_connect.on_connect(status),
+        ) {
+            tcp.close(());
+            return Err(err);
+        }
+        Ok(client)
+    }
+
+    pub fn stream(&self) -> StreamHandle {
+        self.state.borrow().framed.stream()
+    }
+
+    pub fn send<RCB>(&self, mut request: Request, response_cb: RCB) -> Result<(), Errno>
+    where
+        RCB: Into<ResponseCallback<'static>>,
+    {
+        let mut state = self.state.borrow_mut();
+        if state.closed {
+            return Err(Errno::ENOTCONN);
+        }
+        if !request.headers.contains("host") {
+            request.headers.insert("Host", &state.host);
+        }
+
+        if state.connected {
+            state.framed.send(request, ())?;
+        } else {
+            state.queued.push(request);
+        }
+        state.pending.push_back(response_cb.into());
+        Ok(())
+    }
+
+    pub fn send_async(&self, request: Request) -> Completion<Result<Response, Errno>> {
+        let (completer, completion) = completion();
+        let on_response = completer.clone();
+        if let Err(err) = self.send(request, move |response: Result<Response, Errno>| {
+            on_response.complete(response)
+        }) {
+            completer.complete(Err(err));
+        }
+        completion
+    }
+
+    pub fn get_async(&self, target: &str) -> Completion<Result<Response, Errno>> {
+        self.send_async(Request::get(target))
+    }
+
+    pub fn post_async(&self, target: &str, body: &[u8]) -> Completion<Result<Response, Errno>> {
+        self.send_async(Request::post(target, body))
+    }
+
+    // NOTE: requests still waiting on a response fail with ECANCELED
+    pub fn close(&self) {
+        self.shutdown(Errno::ECANCELED);
+    }
+
+    fn on_connect(&self, status: Result<(), Errno>) {
+        if let Err(err) = status {
+            return self.shutdown(err);
+        }
+
+        let result = {
+            let mut state = self.state.borrow_mut();
+            if state.closed {
+                return;
+            }
+            state.connected = true;
+            take(&mut state.queued)
+                .into_iter()
+                .try_for_each(|request| state.framed.send(request, ()))
+        };
+        if let Err(err) = result {
+            return self.shutdown(err);
+        }
+
+        let client = self.clone();
+        let result = self.state.borrow_mut().framed.read_start(
+            move |_: &StreamHandle, response: Result<Response, Errno>| match response {
+                Ok(response) => client.on_response(response),
+                Err(Errno::EOF) => client.shutdown(Errno::ECONNRESET),
+                Err(err) => client.shutdown(err),
+            },
+        );
+        if let Err(err) = result {
+            self.shutdown(err);
+        }
+    }
+
+    fn on_response(&self, response: Response) {
+        let keep_alive = response.keep_alive();
+        let response_cb = self.state.borrow_mut().pending.pop_front();
+        if let Some(mut response_cb) = response_cb {
+            response_cb.0(Ok(response));
+        }
+        if !keep_alive {
+            self.shutdown(Errno::ECONNRESET);
+        }
+    }
+
+    fn shutdown(&self, err: Errno) {
+        let pending = {
+            let mut state = self.state.borrow_mut();
+            if !state.closed {
+                state.closed = true;
+                state.queued.clear();
+                state.framed.read_stop();
+                state.framed.stream().close(());
+            }
+            take(&mut state.pending)
+        };
+        for mut response_cb in pending {
+            response_cb.0(Err(err));
+        }
+    }
+}
+
+// trait
+
+impl<'a, Fn> From<Fn> for ResponseCallback<'a>
+where
+    Fn: FnMut(Result<Response, Errno>) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for ResponseCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_| ()))
+    }
+}
@@ -0,0 +1,695 @@
+use std::{collections::VecDeque, mem::take, str::from_utf8};
+
+use crate::{
+    http::{Headers, Method, Request, Response, Version},
+    uv::{Decoder, Encoder, Errno, find},
+};
+
+// type
+
+pub enum ResponsePart {
+    // NOTE: the body of a head is ignored, it is framed by its own headers and sent as Body parts
+    Head(Response),
+    Body(Vec<u8>),
+    End,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerCodec {
+    max_head_length: usize,
+    max_body_length: usize,
+    request: Option<(Request, BodyDecoder)>,
+    // every decoded request waiting on its response, answered in order
+    pending: VecDeque<(Method, Version)>,
+    chunked: bool,
+    discard: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientCodec {
+    max_head_length: usize,
+    max_body_length: usize,
+    response: Option<(Response, BodyDecoder)>,
+    pending: VecDeque<Method>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Chunk {
+    Size,
+    Data(usize),
+    DataEnd,
+    Trailer,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BodyState {
+    Length(usize),
+    Chunked(Chunk),
+    UntilEof,
+}
+
+#[derive(Debug, Clone)]
+struct BodyDecoder {
+    state: BodyState,
+    body: Vec<u8>,
+    max_length: usize,
+}
+
+// fn
+
+pub const MAX_HEAD_LENGTH: usize = 16 * 1024;
+pub const MAX_BODY_LENGTH: usize = 8 * 1024 * 1024;
+
+fn take_line(buffer: &mut Vec<u8>, max_length: usize) -> Result<Option<String>, Errno> {
+    match find(buffer, b"\r\n") {
+        Some(end) => {
+            let line = from_utf8(&buffer[..end])
+                .map(str::to_string)
+                .map_err(|_| Errno::EPROTO);
+            buffer.drain(..end + 2);
+            line.map(Some)
+        }
+        None if buffer.len() > max_length => Err(Errno::EMSGSIZE),
+        None => Ok(None),
+    }
+}
+
+fn take_head(buffer: &mut Vec<u8>, max_length: usize) -> Result<Option<Vec<String>>, Errno> {
+    // empty lines before a message are tolerated, a client may send one after a body
+    while buffer.starts_with(b"\r\n") {
+        buffer.drain(..2);
+    }
+
+    let Some(end) = find(buffer, b"\r\n\r\n") else {
+        if buffer.len() > max_length {
+            return Err(Errno::EMSGSIZE);
+        }
+        return Ok(None);
+    };
+    if end > max_length {
+        return Err(Errno::EMSGSIZE);
+    }
+
+    let head = from_utf8(&buffer[..end])
+        .map(|head| head.split("\r\n").map(str::to_string).collect())
+        .map_err(|_| Errno::EPROTO);
+    buffer.drain(..end + 4);
+    head.map(Some)
+}
+
+fn parse_headers(lines: &[String]) -> Result<Headers, Errno> {
+    let mut headers = Headers::new();
+    for line in lines {
+        let (name, value) = line.split_once(':').ok_or(Errno::EPROTO)?;
+        if name.is_empty() || name.contains(|c: char| c.is_ascii_whitespace()) {
+            return Err(Errno::EPROTO);
+        }
+        headers.append(name, value.trim());
+    }
+    Ok(headers)
+}
+
+fn body_state(headers: &Headers, max_length: usize) -> Result<Option<BodyState>, Errno> {
+    if let Some(encoding) = headers.get("transfer-encoding") {
+        return match encoding.rsplit(',').next() {
+            Some(last) if last.trim().eq_ignore_ascii_case("chunked") => {
+                Ok(Some(BodyState::Chunked(Chunk::Size)))
+            }
+            _ => Ok(Some(BodyState::UntilEof)),
+        };
+    }
+
+    let mut length = None;
+    for value in headers.get_all("content-length") {
+        let value = value.trim().parse::<usize>().map_err(|_| Errno::EPROTO)?;
+        if length.is_some_and(|length| length != value) {
+            return Err(Errno::EPROTO);
+        }
+        length = Some(value);
+    }
+    match length {
+        Some(length) if length > max_length => Err(Errno::EMSGSIZE),
+        Some(length) => Ok(Some(BodyState::Length(length))),
+        None => Ok(None),
+    }
+}
+
+fn has_body(status: u16) -> bool {
+    !(100..200).contains(&status) && status != 204 && status != 304
+}
+
+// header names are tokens as in RFC 9110, anything else would be read as a different header
+fn is_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|ch| ch.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&ch))
+}
+
+// NOTE: a CR or LF in the start line or a value would let it open a header or message of its own
+fn encode_head(start: &str, headers: &Headers, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+    let invalid = |text: &str| text.contains(['\r', '\n', '\0']);
+    if invalid(start)
+        || headers
+            .iter()
+            .any(|(name, value)| !is_token(name) || invalid(value))
+    {
+        return Err(Errno::EINVAL);
+    }
+
+    buffer.extend_from_slice(start.as_bytes());
+    buffer.extend_from_slice(b"\r\n");
+    for (name, value) in headers.iter() {
+        buffer.extend_from_slice(name.as_bytes());
+        buffer.extend_from_slice(b": ");
+        buffer.extend_from_slice(value.as_bytes());
+        buffer.extend_from_slice(b"\r\n");
+    }
+    buffer.extend_from_slice(b"\r\n");
+    Ok(())
+}
+
+// impl
+
+impl BodyDecoder {
+    fn new(state: BodyState, max_length: usize) -> Self {
+        Self {
+            state,
+            body: Vec::new(),
+            max_length,
+        }
+    }
+
+    fn take(&mut self, buffer: &mut Vec<u8>, remaining: usize) -> Result<usize, Errno> {
+        let len = remaining.min(buffer.len());
+        if self.body.len() + len > self.max_length {
+            return Err(Errno::EMSGSIZE);
+        }
+        self.body.extend(buffer.drain(..len));
+        Ok(remaining - len)
+    }
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Vec<u8>>, Errno> {
+        loop {
+            match self.state {
+                BodyState::Length(remaining) => {
+                    let remaining = self.take(buffer, remaining)?;
+                    self.state = BodyState::Length(remaining);
+                    if remaining > 0 {
+                        return Ok(None);
+                    }
+                    return Ok(Some(take(&mut self.body)));
+                }
+                BodyState::Chunked(Chunk::Size) => {
+                    let Some(line) = take_line(buffer, MAX_HEAD_LENGTH)? else {
+                        return Ok(None);
+                    };
+                    // chunk extensions after ';' carry nothing we use
+                    let size = line.split(';').next().unwrap_or_default().trim();
+                    let size = usize::from_str_radix(size, 16).map_err(|_| Errno::EPROTO)?;
+                    self.state = BodyState::Chunked(match size {
+                        0 => Chunk::Trailer,
+                        size => Chunk::Data(size),
+                    });
+                }
+                BodyState::Chunked(Chunk::Data(remaining)) => {
+                    let remaining = self.take(buffer, remaining)?;
+                    if remaining > 0 {
+                        self.state = BodyState::Chunked(Chunk::Data(remaining));
+                        return Ok(None);
+                    }
+                    self.state = BodyState::Chunked(Chunk::DataEnd);
+                }
+                BodyState::Chunked(Chunk::DataEnd) => {
+                    if buffer.len() < 2 {
+                        return Ok(None);
+                    }
+                    if !buffer.starts_with(b"\r\n") {
+                        return Err(Errno::EPROTO);
+                    }
+                    buffer.drain(..2);
+                    self.state = BodyState::Chunked(Chunk::Size);
+                }
+                BodyState::Chunked(Chunk::Trailer) => {
+                    let Some(line) = take_line(buffer, MAX_HEAD_LENGTH)? else {
+                        return Ok(None);
+                    };
+                    if line.is_empty() {
+                        return Ok(Some(take(&mut self.body)));
+                    }
+                }
+                BodyState::UntilEof => {
+                    let len = buffer.len();
+                    self.take(buffer, len)?;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Vec<u8>>, Errno> {
+        match self.decode(buffer)? {
+            Some(body) => Ok(Some(body)),
+            None => match self.state {
+                BodyState::UntilEof => Ok(Some(take(&mut self.body))),
+                _ => Err(Errno::EPROTO),
+            },
+        }
+    }
+}
+
+impl ServerCodec {
+    pub fn new() -> Self {
+        Self::with_limits(MAX_HEAD_LENGTH, MAX_BODY_LENGTH)
+    }
+
+    pub fn with_limits(max_head_length: usize, max_body_length: usize) -> Self {
+        Self {
+            max_head_length,
+            max_body_length,
+            request: None,
+            pending: VecDeque::new(),
+            chunked: false,
+            discard: false,
+        }
+    }
+
+    fn decode_head(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Request>, Errno> {
+        let Some(lines) = take_head(buffer, self.max_head_length)? else {
+            return Ok(None);
+        };
+
+        let mut start = lines[0].split(' ');
+        let (Some(method), Some(target), Some(version), None) =
+            (start.next(), start.next(), start.next(), start.next())
+        else {
+            return Err(Errno::EPROTO);
+        };
+        if method.is_empty() || target.is_empty() {
+            return Err(Errno::EPROTO);
+        }
+
+        Ok(Some(Request {
+            method: Method::parse(method),
+            target: target.to_string(),
+            version: Version::parse(version).ok_or(Errno::EPROTONOSUPPORT)?,
+            headers: parse_headers(&lines[1..])?,
+            body: Vec::new(),
+        }))
+    }
+}
+
+impl ClientCodec {
+    pub fn new() -> Self {
+        Self::with_limits(MAX_HEAD_LENGTH, MAX_BODY_LENGTH)
+    }
+
+    pub fn with_limits(max_head_length: usize, max_body_length: usize) -> Self {
+        Self {
+            max_head_length,
+            max_body_length,
+            response: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn decode_head(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Response>, Errno> {
+        let Some(lines) = take_head(buffer, self.max_head_length)? else {
+            return Ok(None);
+        };
+
+        let mut start = lines[0].splitn(3, ' ');
+        let (Some(version), Some(status)) = (start.next(), start.next()) else {
+            return Err(Errno::EPROTO);
+        };
+
+        Ok(Some(Response {
+            status: status.parse().map_err(|_| Errno::EPROTO)?,
+            reason: start.next().unwrap_or_default().to_string(),
+            version: Version::parse(version).ok_or(Errno::EPROTONOSUPPORT)?,
+            headers: parse_headers(&lines[1..])?,
+            body: Vec::new(),
+        }))
+    }
+}
+
+// trait
+
+impl Decoder for ServerCodec {
+    type Item = Request;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        let (mut request, mut body) = match self.request.take() {
+            Some(request) => request,
+            None => {
+                let Some(request) = self.decode_head(buffer)? else {
+                    return Ok(None);
+                };
+                // a request without a length has no body, it can never be read until EOF
+                let state = match body_state(&request.headers, self.max_body_length)? {
+                    Some(BodyState::UntilEof) => return Err(Errno::EPROTO),
+                    Some(state) => state,
+                    None => BodyState::Length(0),
+                };
+                (request, BodyDecoder::new(state, self.max_body_length))
+            }
+        };
+
+        match body.decode(buffer)? {
+            Some(data) => {
+                request.body = data;
+                self.pending
+                    .push_back((request.method.clone(), request.version));
+                Ok(Some(request))
+            }
+            None => {
+                self.request = Some((request, body));
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl Encoder<ResponsePart> for ServerCodec {
+    fn encode(&mut self, item: ResponsePart, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        match item {
+            ResponsePart::Head(mut response) => {
+                let (method, version) = self
+                    .pending
+                    .front()
+                    .cloned()
+                    .unwrap_or((Method::GET, Version::HTTP_11));
+
+                let mut chunked = false;
+                if !has_body(response.status) {
+                    response.headers.remove("content-length");
+                    response.headers.remove("transfer-encoding");
+                } else if response.headers.contains("content-length") {
+                    response.headers.remove("transfer-encoding");
+                } else if version == Version::HTTP_11 {
+                    response.headers.insert("Transfer-Encoding", "chunked");
+                    chunked = true;
+                } else {
+                    // an HTTP/1.0 client has no chunked encoding, the body runs until close
+                    response.headers.insert("Connection", "close");
+                }
+                let discard = method == Method::HEAD || !has_body(response.status);
+
+                let start = format!(
+                    "{} {} {}",
+                    Version::HTTP_11,
+                    response.status,
+                    response.reason
+                );
+                encode_head(&start, &response.headers, buffer)?;
+                self.chunked = chunked && !discard;
+                self.discard = discard;
+            }
+            ResponsePart::Body(data) => {
+                if self.discard || data.is_empty() {
+                    return Ok(());
+                }
+                if self.chunked {
+                    buffer.extend_from_slice(format!("{:x}\r\n", data.len()).as_bytes());
+                    buffer.extend_from_slice(&data);
+                    buffer.extend_from_slice(b"\r\n");
+                } else {
+                    buffer.extend_from_slice(&data);
+                }
+            }
+            ResponsePart::End => {
+                if self.chunked {
+                    buffer.extend_from_slice(b"0\r\n\r\n");
+                }
+                self.chunked = false;
+                self.discard = false;
+                self.pending.pop_front();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Encoder<Response> for ServerCodec {
+    fn encode(&mut self, mut item: Response, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        // a HEAD answer may carry the length of the body it leaves out
+        if !(item.body.is_empty() && item.headers.contains("content-length")) {
+            item.headers
+                .insert("Content-Length", &item.body.len().to_string());
+        }
+        let body = take(&mut item.body);
+        self.encode(ResponsePart::Head(item), buffer)?;
+        self.encode(ResponsePart::Body(body), buffer)?;
+        self.encode(ResponsePart::End, buffer)
+    }
+}
+
+impl Default for ServerCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for ClientCodec {
+    type Item = Response;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        loop {
+            let (mut response, mut body) = match self.response.take() {
+                Some(response) => response,
+                None => {
+                    let Some(response) = self.decode_head(buffer)? else {
+                        return Ok(None);
+                    };
+                    // interim responses such as 100 Continue precede the real one
+                    if (100..200).contains(&response.status) && response.status != 101 {
+                        continue;
+                    }
+
+                    let head = self.pending.front() == Some(&Method::HEAD);
+                    let state = if head || !has_body(response.status) {
+                        BodyState::Length(0)
+                    } else {
+                        body_state(&response.headers, self.max_body_length)?
+                            .unwrap_or(BodyState::UntilEof)
+                    };
+                    (response, BodyDecoder::new(state, self.max_body_length))
+                }
+            };
+
+            return match body.decode(buffer)? {
+                Some(data) => {
+                    response.body = data;
+                    self.pending.pop_front();
+                    Ok(Some(response))
+                }
+                None => {
+                    self.response = Some((response, body));
+                    Ok(None)
+                }
+            };
+        }
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        if let Some(response) = self.decode(buffer)? {
+            return Ok(Some(response));
+        }
+
+        match self.response.take() {
+            Some((mut response, mut body)) => {
+                response.body = body.decode_eof(buffer)?.unwrap_or_default();
+                self.pending.pop_front();
+                Ok(Some(response))
+            }
+            None if buffer.is_empty() => Ok(None),
+            None => Err(Errno::EPROTO),
+        }
+    }
+}
+
+impl Encoder<Request> for ClientCodec {
+    fn encode(&mut self, mut item: Request, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        item.headers.remove("transfer-encoding");
+        if !item.body.is_empty()
+            || matches!(item.method, Method::POST | Method::PUT | Method::PATCH)
+        {
+            item.headers
+                .insert("Content-Length", &item.body.len().to_string());
+        }
+
+        let start = format!("{} {} {}", item.method, item.target, item.version);
+        encode_head(&start, &item.headers, buffer)?;
+        buffer.extend_from_slice(&item.body);
+        self.pending.push_back(item.method);
+        Ok(())
+    }
+}
+
+impl Default for ClientCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // feeds the input one read at a time and collects everything decoded along the way
+    fn decode_all<D: Decoder>(codec: &mut D, reads: &[&[u8]]) -> Result<Vec<D::Item>, Errno> {
+        let mut buffer = Vec::new();
+        let mut items = Vec::new();
+        for read in reads {
+            buffer.extend_from_slice(read);
+            while let Some(item) = codec.decode(&mut buffer)? {
+                items.push(item);
+            }
+        }
+        Ok(items)
+    }
+
+    fn bytes(input: &[u8]) -> Vec<&[u8]> {
+        input.chunks(1).collect()
+    }
+
+    #[test]
+    fn server_content_length() {
+        let requests = decode_all(
+            &mut ServerCodec::new(),
+            &[
+                b"POST /a HTTP/1.1\r\nContent-Length: 5\r\n\r\nhel",
+                b"lo",
+                b"GET /b?x=1 HTTP/1.1\r\nHost: here\r\n\r\n",
+            ],
+        )
+        .unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].body, b"hello");
+        assert_eq!(
+            (requests[1].path(), requests[1].query()),
+            ("/b", Some("x=1"))
+        );
+        assert_eq!(requests[1].headers.get("host"), Some("here"));
+        assert!(requests[1].body.is_empty());
+
+        let conflicting = b"POST / HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\n";
+        assert_eq!(
+            decode_all(&mut ServerCodec::new(), &[conflicting]).unwrap_err(),
+            Errno::EPROTO
+        );
+        let too_long = b"POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n";
+        assert_eq!(
+            decode_all(
+                &mut ServerCodec::with_limits(MAX_HEAD_LENGTH, 8),
+                &[too_long]
+            )
+            .unwrap_err(),
+            Errno::EMSGSIZE
+        );
+    }
+
+    #[test]
+    fn server_chunked() {
+        let input = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+            5;name=value\r\nhello\r\n6\r\n world\r\n0\r\nTrailer: x\r\n\r\n";
+        for reads in [vec![&input[..]], bytes(input)] {
+            let requests = decode_all(&mut ServerCodec::new(), &reads).unwrap();
+            assert_eq!(requests.len(), 1);
+            assert_eq!(requests[0].body, b"hello world");
+        }
+
+        let missing_end = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n1\r\naXY";
+        assert_eq!(
+            decode_all(&mut ServerCodec::new(), &[missing_end]).unwrap_err(),
+            Errno::EPROTO
+        );
+        // a request body can not run until EOF
+        let gzip = b"POST / HTTP/1.1\r\nTransfer-Encoding: gzip\r\n\r\n";
+        assert_eq!(
+            decode_all(&mut ServerCodec::new(), &[gzip]).unwrap_err(),
+            Errno::EPROTO
+        );
+    }
+
+    #[test]
+    fn client_content_length_and_chunked() {
+        let mut codec = ClientCodec::new();
+        let mut sent = Vec::new();
+        codec.encode(Request::get("/a"), &mut sent).unwrap();
+        codec
+            .encode(Request::new(Method::HEAD, "/b"), &mut sent)
+            .unwrap();
+        codec.encode(Request::get("/c"), &mut sent).unwrap();
+
+        let input = b"HTTP/1.1 100 Continue\r\n\r\n\
+            HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\nabc\
+            HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\n\
+            HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n4\r\ngone\r\n0\r\n\r\n";
+        let responses = decode_all(&mut codec.clone(), &[input]).unwrap();
+        assert_eq!(responses, decode_all(&mut codec, &bytes(input)).unwrap());
+        assert_eq!(responses.len(), 3);
+        assert_eq!(
+            (responses[0].status, &responses[0].body[..]),
+            (200, &b"abc"[..])
+        );
+        // the answer to HEAD announces a length but carries no body
+        assert!(responses[1].body.is_empty());
+        assert_eq!(responses[2].reason, "Not Found");
+        assert_eq!(responses[2].body, b"gone");
+    }
+
+    #[test]
+    fn client_until_eof() {
+        let mut codec = ClientCodec::new();
+        codec.encode(Request::get("/"), &mut Vec::new()).unwrap();
+
+        let mut buffer = b"HTTP/1.0 200 OK\r\n\r\nall of it".to_vec();
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+        let response = codec.decode_eof(&mut buffer).unwrap().unwrap();
+        assert_eq!(response.version, Version::HTTP_10);
+        assert_eq!(response.body, b"all of it");
+
+        let mut buffer = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nab".to_vec();
+        codec.encode(Request::get("/"), &mut Vec::new()).unwrap();
+        assert_eq!(codec.decode_eof(&mut buffer).unwrap_err(), Errno::EPROTO);
+    }
+
+    #[test]
+    fn encode_rejects_invalid_headers() {
+        let mut buffer = Vec::new();
+        for request in [
+            Request::get("/").with_header("Bad Name", "x"),
+            Request::get("/").with_header("", "x"),
+            Request::get("/").with_header("X-Split", "a\r\nInjected: 1"),
+            Request::get("/").with_header("X-Nul", "a\0b"),
+            Request::get("/ HTTP/1.1\r\nInjected: 1\r\n"),
+        ] {
+            assert_eq!(
+                ClientCodec::new().encode(request, &mut buffer),
+                Err(Errno::EINVAL)
+            );
+        }
+        assert!(buffer.is_empty());
+
+        let mut codec = ServerCodec::new();
+        let response = Response::new(200).with_header("X-Split", "a\nb");
+        assert_eq!(
+            codec.encode(ResponsePart::Head(response), &mut buffer),
+            Err(Errno::EINVAL)
+        );
+        assert!(buffer.is_empty());
+
+        codec
+            .encode(
+                Response::text(200, "ok").with_header("X-Tag", "a b"),
+                &mut buffer,
+            )
+            .unwrap();
+        let head = from_utf8(&buffer).unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("X-Tag: a b\r\n"));
+        assert!(head.ends_with("\r\n\r\nok"));
+    }
+}
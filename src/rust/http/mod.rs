@@ -0,0 +1,11 @@
+pub mod message;
+pub use message::*;
+
+pub mod codec;
+pub use codec::*;
+
+pub mod server;
+pub use server::*;
+
+pub mod client;
+pub use client::*;
@@ -0,0 +1,453 @@
+use std::{cell::RefCell, collections::VecDeque, net::SocketAddr, rc::Rc};
+
+use crate::{
+    http::{Method, Request, Response, ResponsePart, ServerCodec, Version, reason_phrase},
+    uv::{
+        Encoder, Errno, FramedStream, IHandle, IStreamHandle, Loop, ShutdownRequest, StreamHandle,
+        TCPStream,
+    },
+};
+
+// type
+
+pub struct RouteHandler<'a>(pub Box<dyn FnMut(Request, ResponseWriter) + 'a>);
+
+struct Route {
+    method: Method,
+    path: String,
+    handler: Rc<RefCell<RouteHandler<'static>>>,
+}
+
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<Route>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriterState {
+    Pending,
+    Streaming,
+    Done,
+}
+
+// NOTE: answers exactly one request, dropping it unanswered replies 500 and an open stream is ended
+pub struct ResponseWriter {
+    connection: Connection,
+    response: Response,
+    state: WriterState,
+}
+
+struct ConnectionState {
+    framed: FramedStream<ServerCodec>,
+    router: Rc<Router>,
+    queue: VecDeque<Request>,
+    // the request being answered, responses go out strictly in request order
+    current: Option<(Version, bool)>,
+    dispatching: bool,
+    eof: bool,
+    closed: bool,
+}
+
+#[derive(Clone)]
+struct Connection {
+    state: Rc<RefCell<ConnectionState>>,
+}
+
+pub struct HttpServer {
+    listener: TCPStream,
+}
+
+// fn
+
+const BACKLOG: i32 = 128;
+
+fn matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => {
+            path == prefix
+                || path
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }
+        None => pattern == "*" || pattern == path,
+    }
+}
+
+fn accept(mut server: StreamHandle, router: Rc<Router>) -> Result<(), Errno> {
+    let mut client = server.get_loop().new_tcp()?.into_stream();
+    if let Err(err) = server.accept(&mut client) {
+        client.close(());
+        return Err(err);
+    }
+
+    let connection = Connection {
+        state: Rc::new(RefCell::new(ConnectionState {
+            framed: FramedStream::new(client, ServerCodec::new()),
+            router,
+            queue: VecDeque::new(),
+            current: None,
+            dispatching: false,
+            eof: false,
+            closed: false,
+        })),
+    };
+    connection.start()
+}
+
+// impl
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // NOTE: a path ending in /* also matches everything below it, a HEAD request falls back to GET
+    pub fn route<RH>(mut self, method: Method, path: &str, handler: RH) -> Self
+    where
+        RH: Into<RouteHandler<'static>>,
+    {
+        self.routes.push(Route {
+            method,
+            path: path.to_string(),
+            handler: Rc::new(RefCell::new(handler.into())),
+        });
+        self
+    }
+
+    pub fn get<RH>(self, path: &str, handler: RH) -> Self
+    where
+        RH: Into<RouteHandler<'static>>,
+    {
+        self.route(Method::GET, path, handler)
+    }
+
+    pub fn post<RH>(self, path: &str, handler: RH) -> Self
+    where
+        RH: Into<RouteHandler<'static>>,
+    {
+        self.route(Method::POST, path, handler)
+    }
+
+    pub fn put<RH>(self, path: &str, handler: RH) -> Self
+    where
+        RH: Into<RouteHandler<'static>>,
+    {
+        self.route(Method::PUT, path, handler)
+    }
+
+    pub fn delete<RH>(self, path: &str, handler: RH) -> Self
+    where
+        RH: Into<RouteHandler<'static>>,
+    {
+        self.route(Method::DELETE, path, handler)
+    }
+
+    fn find(
+        &self,
+        method: &Method,
+        path: &str,
+    ) -> Result<Rc<RefCell<RouteHandler<'static>>>, Vec<Method>> {
+        let routes: Vec<&Route> = self
+            .routes
+            .iter()
+            .filter(|route| matches(&route.path, path))
+            .collect();
+
+        let route = routes
+            .iter()
+            .find(|route| &route.method == method)
+            .or_else(|| match method {
+                Method::HEAD => routes.iter().find(|route| route.method == Method::GET),
+                _ => None,
+            });
+        match route {
+            Some(route) => Ok(route.handler.clone()),
+            None => Err(routes.iter().map(|route| route.method.clone()).collect()),
+        }
+    }
+}
+
+impl ResponseWriter {
+    pub fn status(&mut self, status: u16) -> &mut Self {
+        self.response.status = status;
+        self.response.reason = reason_phrase(status).to_string();
+        self
+    }
+
+    pub fn header(&mut self, name: &str, value: &str) -> &mut Self {
+        self.response.headers.insert(name, value);
+        self
+    }
+
+    pub fn send(mut self, response: Response) -> Result<(), Errno> {
+        if self.state != WriterState::Pending {
+            return Err(Errno::EALREADY);
+        }
+
+        self.state = WriterState::Done;
+        let result = self
+            .connection
+            .send(self.connection.prepare(response, false));
+        self.connection.finish();
+        result
+    }
+
+    // NOTE: the head goes out with the first write, chunked unless a Content-Length was set
+    pub fn write(&mut self, data: &[u8]) -> Result<(), Errno> {
+        match self.state {
+            WriterState::Pending => {
+                self.state = WriterState::Streaming;
+                let response = self.connection.prepare(self.response.clone(), true);
+                self.connection.send(ResponsePart::Head(response))?;
+            }
+            WriterState::Streaming => {}
+            WriterState::Done => return Err(Errno::EALREADY),
+        }
+        self.connection.send(ResponsePart::Body(data.to_vec()))
+    }
+
+    pub fn end(mut self) -> Result<(), Errno> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Result<(), Errno> {
+        let result = match self.state {
+            WriterState::Pending => {
+                self.state = WriterState::Done;
+                let response = self.connection.prepare(self.response.clone(), false);
+                self.connection.send(response)
+            }
+            WriterState::Streaming => {
+                self.state = WriterState::Done;
+                self.connection.send(ResponsePart::End)
+            }
+            WriterState::Done => return Ok(()),
+        };
+        self.connection.finish();
+        result
+    }
+}
+
+impl Connection {
+    fn start(&self) -> Result<(), Errno> {
+        let connection = self.clone();
+        self.state.borrow_mut().framed.read_start(
+            move |_: &StreamHandle, request: Result<Request, Errno>| match request {
+                Ok(request) => {
+                    connection.state.borrow_mut().queue.push_back(request);
+                    connection.dispatch();
+                }
+                Err(Errno::EOF) => {
+                    let idle = {
+                        let mut state = connection.state.borrow_mut();
+                        state.eof = true;
+                        state.current.is_none() && state.queue.is_empty()
+                    };
+                    if idle {
+                        connection.close();
+                    }
+                }
+                Err(err) => connection.fail(err),
+            },
+        )
+    }
+
+    fn dispatch(&self) {
+        if self.state.borrow().dispatching {
+            return;
+        }
+
+        // handlers that answer right away finish inside this loop instead of recursing into it
+        self.state.borrow_mut().dispatching = true;
+        loop {
+            let request = {
+                let mut state = self.state.borrow_mut();
+                if state.closed || state.current.is_some() {
+                    break;
+                }
+                let Some(request) = state.queue.pop_front() else {
+                    break;
+                };
+                state.current = Some((request.version, request.keep_alive()));
+                request
+            };
+
+            let route = self
+                .state
+                .borrow()
+                .router
+                .find(&request.method, request.path());
+            let writer = ResponseWriter {
+                connection: self.clone(),
+                response: Response::new(200),
+                state: WriterState::Pending,
+            };
+            match route {
+                Ok(handler) => handler.borrow_mut().0(request, writer),
+                Err(allowed) if allowed.is_empty() => {
+                    let _ = writer.send(Response::text(404, "Not Found"));
+                }
+                Err(allowed) => {
+                    let allowed: Vec<&str> = allowed.iter().map(Method::as_str).collect();
+                    let _ = writer.send(
+                        Response::text(405, "Method Not Allowed")
+                            .with_header("Allow", &allowed.join(", ")),
+                    );
+                }
+            }
+        }
+        self.state.borrow_mut().dispatching = false;
+    }
+
+    fn prepare(&self, mut response: Response, streaming: bool) -> Response {
+        let mut state = self.state.borrow_mut();
+        let Some((version, keep_alive)) = state.current.as_mut() else {
+            return response;
+        };
+
+        // an HTTP/1.0 peer can only tell where a streamed body ends by the connection closing
+        if streaming && *version == Version::HTTP_10 && !response.headers.contains("content-length")
+        {
+            *keep_alive = false;
+        }
+        if !response.keep_alive() {
+            *keep_alive = false;
+        }
+
+        if !*keep_alive {
+            response.headers.insert("Connection", "close");
+        } else if *version == Version::HTTP_10 {
+            response.headers.insert("Connection", "keep-alive");
+        }
+        response
+    }
+
+    fn send<I>(&self, item: I) -> Result<(), Errno>
+    where
+        ServerCodec: Encoder<I>,
+    {
+        let mut state = self.state.borrow_mut();
+        if state.closed {
+            return Err(Errno::ENOTCONN);
+        }
+        state.framed.send(item, ())
+    }
+
+    fn finish(&self) {
+        let close = {
+            let mut state = self.state.borrow_mut();
+            let keep_alive = state
+                .current
+                .take()
+                .is_some_and(|(_, keep_alive)| keep_alive);
+            !keep_alive || (state.eof && state.queue.is_empty())
+        };
+        if close {
+            self.close();
+        } else {
+            self.dispatch();
+        }
+    }
+
+    fn fail(&self, err: Errno) {
+        let idle = self.state.borrow().current.is_none();
+        if idle {
+            let status = match err {
+                Errno::EMSGSIZE => 413,
+                Errno::EPROTONOSUPPORT => 505,
+                _ => 400,
+            };
+            let response =
+                Response::text(status, reason_phrase(status)).with_header("Connection", "close");
+            let _ = self.send(response);
+            self.close();
+        } else {
+            // the response in flight is still delivered, nothing after it is
+            let mut state = self.state.borrow_mut();
+            state.eof = true;
+            state.queue.clear();
+        }
+    }
+
+    fn close(&self) {
+        let mut stream = {
+            let mut state = self.state.borrow_mut();
+            if state.closed {
+                return;
+            }
+            state.closed = true;
+            state.queue.clear();
+            state.framed.read_stop();
+            state.framed.stream()
+        };
+
+        // shutdown flushes the writes still queued before the handle goes away
+        let result = stream.shutdown(
+            ShutdownRequest::new(),
+            move |_: ShutdownRequest, _: Result<(), Errno>| {
+                let mut stream = stream;
+                stream.close(());
+            },
+        );
+        if result.is_err() {
+            stream.close(());
+        }
+    }
+}
+
+impl HttpServer {
+    pub fn bind(r#loop: &Loop, addr: &SocketAddr, router: Router) -> Result<Self, Errno> {
+        let mut listener = r#loop.new_tcp()?;
+        if let Err(err) = listener.bind(addr) {
+            listener.close(());
+            return Err(err);
+        }
+
+        let router = Rc::new(router);
+        if let Err(err) = listener.listen(
+            BACKLOG,
+            move |server: &StreamHandle, status: Result<(), Errno>| {
+                if status.is_ok() {
+                    let _ = accept(*server, router.clone());
+                }
+            },
+        ) {
+            listener.close(());
+            return Err(err);
+        }
+        Ok(Self { listener })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, Errno> {
+        self.listener.get_sockname()
+    }
+
+    pub fn listener(&self) -> TCPStream {
+        self.listener
+    }
+
+    // NOTE: only stops accepting, connections already open finish their requests
+    pub fn close(mut self) {
+        self.listener.close(());
+    }
+}
+
+// trait
+
+impl Drop for ResponseWriter {
+    fn drop(&mut self) {
+        if self.state == WriterState::Pending {
+            self.response = Response::text(500, "Internal Server Error");
+        }
+        let _ = self.close();
+    }
+}
+
+impl<'a, Fn> From<Fn> for RouteHandler<'a>
+where
+    Fn: FnMut(Request, ResponseWriter) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
@@ -1,4 +1,6 @@
+pub mod http;
 pub mod inners;
+pub mod rpc;
 pub mod tea;
 pub mod uv;
 
@@ -0,0 +1,220 @@
+use std::str::from_utf8;
+
+use crate::uv::{Decoder, DelimiterCodec, Encoder, Errno, find};
+
+// type
+
+#[derive(Debug, Clone)]
+pub struct ContentLengthCodec {
+    max_length: usize,
+    content_length: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum RpcCodec {
+    ContentLength(ContentLengthCodec),
+    Newline(DelimiterCodec),
+}
+
+// fn
+
+const HEADER_END: &[u8] = b"\r\n\r\n";
+const MAX_HEADER_LENGTH: usize = 8192;
+
+fn content_length(header: &[u8]) -> Result<usize, Errno> {
+    let header = from_utf8(header).map_err(|_| Errno::EPROTO)?;
+    let mut content_length = None;
+    for line in header.split("\r\n") {
+        let (name, value) = line.split_once(':').ok_or(Errno::EPROTO)?;
+        // NOTE: other headers such as Content-Type are accepted and ignored
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.trim().parse().map_err(|_| Errno::EPROTO)?);
+        }
+    }
+    content_length.ok_or(Errno::EPROTO)
+}
+
+// impl
+
+impl ContentLengthCodec {
+    pub fn new() -> Self {
+        Self::with_max_length(u32::MAX as usize)
+    }
+
+    pub fn with_max_length(max_length: usize) -> Self {
+        Self {
+            max_length,
+            content_length: None,
+        }
+    }
+}
+
+impl RpcCodec {
+    pub fn content_length() -> Self {
+        Self::ContentLength(ContentLengthCodec::new())
+    }
+
+    pub fn newline() -> Self {
+        Self::Newline(
+            DelimiterCodec::new(b"\n").expect("RpcCodec::newline: the delimiter is not empty"),
+        )
+    }
+}
+
+// trait
+
+impl Decoder for ContentLengthCodec {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        let len = match self.content_length {
+            Some(len) => len,
+            None => {
+                let Some(end) = find(buffer, HEADER_END) else {
+                    if buffer.len() > MAX_HEADER_LENGTH {
+                        return Err(Errno::EMSGSIZE);
+                    }
+                    return Ok(None);
+                };
+
+                let len = content_length(&buffer[..end]);
+                buffer.drain(..end + HEADER_END.len());
+                let len = len?;
+                if len > self.max_length {
+                    return Err(Errno::EMSGSIZE);
+                }
+                self.content_length = Some(len);
+                len
+            }
+        };
+        if buffer.len() < len {
+            return Ok(None);
+        }
+
+        self.content_length = None;
+        Ok(Some(buffer.drain(..len).collect()))
+    }
+}
+
+impl Encoder<Vec<u8>> for ContentLengthCodec {
+    fn encode(&mut self, item: Vec<u8>, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        if item.len() > self.max_length {
+            return Err(Errno::EMSGSIZE);
+        }
+        buffer.extend_from_slice(format!("Content-Length: {}\r\n\r\n", item.len()).as_bytes());
+        buffer.extend_from_slice(&item);
+        Ok(())
+    }
+}
+
+impl Default for ContentLengthCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for RpcCodec {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        match self {
+            Self::ContentLength(codec) => codec.decode(buffer),
+            Self::Newline(codec) => codec.decode(buffer),
+        }
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        match self {
+            Self::ContentLength(codec) => codec.decode_eof(buffer),
+            Self::Newline(codec) => codec.decode_eof(buffer),
+        }
+    }
+}
+
+impl Encoder<Vec<u8>> for RpcCodec {
+    fn encode(&mut self, item: Vec<u8>, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        match self {
+            Self::ContentLength(codec) => codec.encode(item, buffer),
+            Self::Newline(codec) => codec.encode(item, buffer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(codec: &mut RpcCodec, reads: &[&[u8]]) -> Result<Vec<Vec<u8>>, Errno> {
+        let mut buffer = Vec::new();
+        let mut frames = Vec::new();
+        for read in reads {
+            buffer.extend_from_slice(read);
+            while let Some(frame) = codec.decode(&mut buffer)? {
+                frames.push(frame);
+            }
+        }
+        Ok(frames)
+    }
+
+    #[test]
+    fn content_length_frames() {
+        let mut codec = RpcCodec::content_length();
+        let mut buffer = Vec::new();
+        codec.encode(b"{}".to_vec(), &mut buffer).unwrap();
+        codec.encode(b"[1]".to_vec(), &mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            b"Content-Length: 2\r\n\r\n{}Content-Length: 3\r\n\r\n[1]"
+        );
+
+        let reads: Vec<_> = buffer.chunks(1).collect();
+        assert_eq!(
+            decode_all(&mut codec, &reads).unwrap(),
+            [b"{}".to_vec(), b"[1]".to_vec()]
+        );
+
+        // other headers are skipped and the name is matched in any case
+        let frames = decode_all(
+            &mut codec,
+            &[b"content-length: 4\r\nContent-Type: application/json\r\n\r\nnull"],
+        )
+        .unwrap();
+        assert_eq!(frames, [b"null".to_vec()]);
+    }
+
+    #[test]
+    fn content_length_errors() {
+        for input in [
+            &b"Content-Type: x\r\n\r\n{}"[..],
+            b"Content-Length: two\r\n\r\n{}",
+            b"Content-Length 2\r\n\r\n{}",
+        ] {
+            assert_eq!(
+                decode_all(&mut RpcCodec::content_length(), &[input]),
+                Err(Errno::EPROTO)
+            );
+        }
+
+        let mut codec = RpcCodec::ContentLength(ContentLengthCodec::with_max_length(2));
+        assert_eq!(
+            decode_all(&mut codec, &[b"Content-Length: 3\r\n\r\n"]),
+            Err(Errno::EMSGSIZE)
+        );
+        assert_eq!(
+            codec.encode(b"abc".to_vec(), &mut Vec::new()),
+            Err(Errno::EMSGSIZE)
+        );
+
+        let header = vec![b'x'; MAX_HEADER_LENGTH + 1];
+        assert_eq!(
+            decode_all(&mut RpcCodec::content_length(), &[&header]),
+            Err(Errno::EMSGSIZE)
+        );
+    }
+
+    #[test]
+    fn newline_frames() {
+        let frames = decode_all(&mut RpcCodec::newline(), &[b"{\"a\":", b"1}\n[]\n"]).unwrap();
+        assert_eq!(frames, [b"{\"a\":1}".to_vec(), b"[]".to_vec()]);
+    }
+}
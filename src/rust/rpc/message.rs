@@ -0,0 +1,365 @@
+use std::{error::Error, fmt::Display};
+
+use serde_json::{Map, Value};
+
+use crate::uv::Errno;
+
+// type
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RequestId {
+    Number(i64),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Request {
+        id: RequestId,
+        method: String,
+        params: Value,
+    },
+    Notification {
+        method: String,
+        params: Value,
+    },
+    // NOTE: id is None only for errors answering a request whose id could not be read
+    Response {
+        id: Option<RequestId>,
+        result: Result<Value, RpcError>,
+    },
+}
+
+// fn
+
+fn params(object: &Map<String, Value>) -> Result<Value, RpcError> {
+    match object.get("params") {
+        None => Ok(Value::Null),
+        Some(params) if params.is_array() || params.is_object() => Ok(params.clone()),
+        Some(_) => Err(RpcError::invalid_request()),
+    }
+}
+
+// impl
+
+impl RequestId {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(number) => number.as_i64().map(Self::Number),
+            Value::String(string) => Some(Self::String(string.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::Number(number) => Value::from(*number),
+            Self::String(string) => Value::from(string.as_str()),
+        }
+    }
+}
+
+impl RpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    // NOTE: not part of JSON-RPC 2.0, reserved by LSP for requests answered after $/cancelRequest
+    pub const REQUEST_CANCELLED: i64 = -32800;
+
+    pub fn new(code: i64, message: &str) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn parse_error() -> Self {
+        Self::new(Self::PARSE_ERROR, "Parse error")
+    }
+
+    pub fn invalid_request() -> Self {
+        Self::new(Self::INVALID_REQUEST, "Invalid Request")
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(
+            Self::METHOD_NOT_FOUND,
+            &format!("Method not found: {}", method),
+        )
+    }
+
+    pub fn invalid_params(message: &str) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    pub fn internal_error(message: &str) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
+
+    pub fn request_cancelled() -> Self {
+        Self::new(Self::REQUEST_CANCELLED, "Request cancelled")
+    }
+
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = value.as_object()?;
+        Some(Self {
+            code: object.get("code")?.as_i64()?,
+            message: object.get("message")?.as_str()?.to_string(),
+            data: object.get("data").cloned(),
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("code".to_string(), Value::from(self.code));
+        object.insert("message".to_string(), Value::from(self.message.as_str()));
+        if let Some(data) = &self.data {
+            object.insert("data".to_string(), data.clone());
+        }
+        Value::Object(object)
+    }
+}
+
+impl Message {
+    pub fn from_value(value: &Value) -> Result<Self, RpcError> {
+        let object = value.as_object().ok_or_else(RpcError::invalid_request)?;
+        if object.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+            return Err(RpcError::invalid_request());
+        }
+
+        if let Some(method) = object.get("method") {
+            let method = method
+                .as_str()
+                .ok_or_else(RpcError::invalid_request)?
+                .to_string();
+            let params = params(object)?;
+            return match object.get("id") {
+                None => Ok(Self::Notification { method, params }),
+                Some(id) => Ok(Self::Request {
+                    id: RequestId::from_value(id).ok_or_else(RpcError::invalid_request)?,
+                    method,
+                    params,
+                }),
+            };
+        }
+
+        let id = match object.get("id") {
+            Some(Value::Null) => None,
+            Some(id) => Some(RequestId::from_value(id).ok_or_else(RpcError::invalid_request)?),
+            None => return Err(RpcError::invalid_request()),
+        };
+        match (object.get("result"), object.get("error")) {
+            (Some(result), None) => Ok(Self::Response {
+                id,
+                result: Ok(result.clone()),
+            }),
+            (None, Some(error)) => Ok(Self::Response {
+                id,
+                result: Err(RpcError::from_value(error).ok_or_else(RpcError::invalid_request)?),
+            }),
+            _ => Err(RpcError::invalid_request()),
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("jsonrpc".to_string(), Value::from("2.0"));
+        match self {
+            Self::Request { id, method, params } => {
+                object.insert("id".to_string(), id.to_value());
+                object.insert("method".to_string(), Value::from(method.as_str()));
+                if !params.is_null() {
+                    object.insert("params".to_string(), params.clone());
+                }
+            }
+            Self::Notification { method, params } => {
+                object.insert("method".to_string(), Value::from(method.as_str()));
+                if !params.is_null() {
+                    object.insert("params".to_string(), params.clone());
+                }
+            }
+            Self::Response { id, result } => {
+                object.insert(
+                    "id".to_string(),
+                    id.as_ref().map_or(Value::Null, RequestId::to_value),
+                );
+                match result {
+                    Ok(result) => object.insert("result".to_string(), result.clone()),
+                    Err(error) => object.insert("error".to_string(), error.to_value()),
+                };
+            }
+        }
+        Value::Object(object)
+    }
+}
+
+// trait
+
+impl Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{}", number),
+            Self::String(string) => write!(f, "{:?}", string),
+        }
+    }
+}
+
+impl From<i64> for RequestId {
+    fn from(value: i64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<&str> for RequestId {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RpcError [{}]: {}", self.code, self.message)
+    }
+}
+
+impl Error for RpcError {}
+
+impl From<Errno> for RpcError {
+    fn from(value: Errno) -> Self {
+        Self::internal_error(&value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn invalid(value: Value) {
+        assert_eq!(
+            Message::from_value(&value),
+            Err(RpcError::invalid_request()),
+            "{}",
+            value
+        );
+    }
+
+    #[test]
+    fn requests_and_notifications() {
+        let value = json!({"jsonrpc": "2.0", "id": 1, "method": "sum", "params": [1, 2]});
+        let message = Message::from_value(&value).unwrap();
+        assert_eq!(
+            message,
+            Message::Request {
+                id: RequestId::Number(1),
+                method: "sum".to_string(),
+                params: json!([1, 2]),
+            }
+        );
+        assert_eq!(message.to_value(), value);
+
+        let value = json!({"jsonrpc": "2.0", "id": "a", "method": "ping"});
+        assert_eq!(
+            Message::from_value(&value).unwrap(),
+            Message::Request {
+                id: RequestId::from("a"),
+                method: "ping".to_string(),
+                params: Value::Null,
+            }
+        );
+
+        let value = json!({"jsonrpc": "2.0", "method": "exit", "params": {"code": 0}});
+        let message = Message::from_value(&value).unwrap();
+        assert_eq!(
+            message,
+            Message::Notification {
+                method: "exit".to_string(),
+                params: json!({"code": 0}),
+            }
+        );
+        assert_eq!(message.to_value(), value);
+    }
+
+    #[test]
+    fn responses() {
+        let value = json!({"jsonrpc": "2.0", "id": 7, "result": null});
+        let message = Message::from_value(&value).unwrap();
+        assert_eq!(
+            message,
+            Message::Response {
+                id: Some(RequestId::Number(7)),
+                result: Ok(Value::Null),
+            }
+        );
+        assert_eq!(message.to_value(), value);
+
+        let value = json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {"code": -32700, "message": "Parse error", "data": "line 1"},
+        });
+        let message = Message::from_value(&value).unwrap();
+        assert_eq!(
+            message,
+            Message::Response {
+                id: None,
+                result: Err(RpcError::parse_error().with_data(json!("line 1"))),
+            }
+        );
+        assert_eq!(message.to_value(), value);
+    }
+
+    #[test]
+    fn batch_items() {
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "a"},
+            {"jsonrpc": "2.0", "method": "b"},
+            {"jsonrpc": "2.0", "id": 1, "result": 2},
+            1,
+        ]);
+        // NOTE: the peer splits a batch, the array itself is not a message
+        invalid(batch.clone());
+
+        let items: Vec<_> = batch
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(Message::from_value)
+            .collect();
+        assert!(matches!(items[0], Ok(Message::Request { .. })));
+        assert!(matches!(items[1], Ok(Message::Notification { .. })));
+        assert!(matches!(items[2], Ok(Message::Response { .. })));
+        assert_eq!(items[3], Err(RpcError::invalid_request()));
+    }
+
+    #[test]
+    fn invalid_messages() {
+        invalid(json!("2.0"));
+        invalid(json!({"id": 1, "method": "a"}));
+        invalid(json!({"jsonrpc": "1.0", "id": 1, "method": "a"}));
+        invalid(json!({"jsonrpc": "2.0", "id": 1, "method": 5}));
+        invalid(json!({"jsonrpc": "2.0", "id": 1.5, "method": "a"}));
+        invalid(json!({"jsonrpc": "2.0", "id": [1], "method": "a"}));
+        invalid(json!({"jsonrpc": "2.0", "method": "a", "params": 1}));
+        invalid(json!({"jsonrpc": "2.0", "result": 1}));
+        invalid(json!({"jsonrpc": "2.0", "id": 1}));
+        invalid(json!({"jsonrpc": "2.0", "id": 1, "result": 1, "error": {}}));
+        invalid(json!({"jsonrpc": "2.0", "id": 1, "error": {"code": "x", "message": ""}}));
+    }
+}
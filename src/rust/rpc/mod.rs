@@ -0,0 +1,8 @@
+pub mod message;
+pub use message::*;
+
+pub mod codec;
+pub use codec::*;
+
+pub mod peer;
+pub use peer::*;
@@ -0,0 +1,522 @@
+use std::{cell::RefCell, collections::HashMap, mem::take, rc::Rc};
+
+use serde_json::{Value, json};
+
+use crate::{
+    rpc::{Message, RequestId, RpcCodec, RpcError},
+    uv::{
+        Completion, Errno, FramedStream, HandleType, IHandle, IStreamHandle, Loop, StreamHandle,
+        completion, guess_handle,
+    },
+};
+
+// type
+
+pub struct MethodHandler<'a>(pub Box<dyn FnMut(&RpcPeer, Value) -> Result<Value, RpcError> + 'a>);
+
+pub struct DeferredHandler<'a>(pub Box<dyn FnMut(&RpcPeer, Value, Responder) + 'a>);
+
+pub struct NotificationHandler<'a>(pub Box<dyn FnMut(&RpcPeer, Value) + 'a>);
+
+pub struct ResponseCallback<'a>(pub Box<dyn FnMut(&RpcPeer, Result<Value, RpcError>) + 'a>);
+
+pub struct DisconnectCallback<'a>(pub Box<dyn FnMut(&RpcPeer, Errno) + 'a>);
+
+// NOTE: answers exactly one incoming request, dropping it unanswered replies with an internal error
+pub struct Responder {
+    peer: RpcPeer,
+    id: RequestId,
+    batch: Option<Rc<RefCell<Batch>>>,
+    responded: bool,
+}
+
+#[derive(Clone)]
+pub struct RpcPeer {
+    state: Rc<RefCell<PeerState>>,
+}
+
+struct Batch {
+    remaining: usize,
+    responses: Vec<Value>,
+}
+
+struct PeerState {
+    reader: FramedStream<RpcCodec>,
+    writer: FramedStream<RpcCodec>,
+    next_id: i64,
+    pending: HashMap<RequestId, ResponseCallback<'static>>,
+    methods: HashMap<String, Rc<RefCell<DeferredHandler<'static>>>>,
+    notifications: HashMap<String, Rc<RefCell<NotificationHandler<'static>>>>,
+    // incoming requests still waiting on their responder, flagged once the other side cancels them
+    incoming: HashMap<RequestId, bool>,
+    disconnect_cb: Option<DisconnectCallback<'static>>,
+}
+
+// fn
+
+pub const CANCEL_REQUEST: &str = "$/cancelRequest";
+
+fn open_stdio(r#loop: &Loop, fd: i32) -> Result<StreamHandle, Errno> {
+    match guess_handle(fd) {
+        HandleType::TTY => Ok(r#loop.new_tty(fd)?.into_stream()),
+        HandleType::NAMED_PIPE => {
+            let mut pipe = r#loop.new_pipe(false)?;
+            if let Err(err) = pipe.open(fd) {
+                pipe.close(());
+                return Err(err);
+            }
+            Ok(pipe.into_stream())
+        }
+        _ => Err(Errno::ENOTSUP),
+    }
+}
+
+// impl
+
+impl Responder {
+    pub fn id(&self) -> &RequestId {
+        &self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.peer
+            .state
+            .borrow()
+            .incoming
+            .get(&self.id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    // NOTE: a cancelled request is always answered with REQUEST_CANCELLED whatever the result
+    pub fn respond(mut self, result: Result<Value, RpcError>) -> Result<(), Errno> {
+        self.responded = true;
+        self.peer.respond(&self.id, self.batch.as_ref(), result)
+    }
+}
+
+impl RpcPeer {
+    pub fn new<S: IStreamHandle>(stream: S, codec: RpcCodec) -> Self {
+        let stream = stream.into_stream();
+        Self::with_streams(stream, stream, codec)
+    }
+
+    pub fn with_streams<R, W>(reader: R, writer: W, codec: RpcCodec) -> Self
+    where
+        R: IStreamHandle,
+        W: IStreamHandle,
+    {
+        Self {
+            state: Rc::new(RefCell::new(PeerState {
+                reader: FramedStream::new(reader, codec.clone()),
+                writer: FramedStream::new(writer, codec),
+                next_id: 0,
+                pending: HashMap::new(),
+                methods: HashMap::new(),
+                notifications: HashMap::new(),
+                incoming: HashMap::new(),
+                disconnect_cb: None,
+            })),
+        }
+    }
+
+    pub fn content_length<S: IStreamHandle>(stream: S) -> Self {
+        Self::new(stream, RpcCodec::content_length())
+    }
+
+    pub fn newline<S: IStreamHandle>(stream: S) -> Self {
+        Self::new(stream, RpcCodec::newline())
+    }
+
+    // NOTE: stdin and stdout must each be a TTY or a pipe, regular files cannot be streamed
+    pub fn stdio(r#loop: &Loop, codec: RpcCodec) -> Result<Self, Errno> {
+        let mut reader = open_stdio(r#loop, 0)?;
+        let writer = match open_stdio(r#loop, 1) {
+            Ok(writer) => writer,
+            Err(err) => {
+                reader.close(());
+                return Err(err);
+            }
+        };
+        Ok(Self::with_streams(reader, writer, codec))
+    }
+
+    pub fn reader(&self) -> StreamHandle {
+        self.state.borrow().reader.stream()
+    }
+
+    pub fn writer(&self) -> StreamHandle {
+        self.state.borrow().writer.stream()
+    }
+
+    pub fn method<MH>(&self, name: &str, handler: MH)
+    where
+        MH: Into<MethodHandler<'static>>,
+    {
+        let mut handler = handler.into();
+        self.deferred_method(
+            name,
+            move |peer: &RpcPeer, params: Value, responder: Responder| {
+                let _ = responder.respond(handler.0(peer, params));
+            },
+        );
+    }
+
+    pub fn deferred_method<DH>(&self, name: &str, handler: DH)
+    where
+        DH: Into<DeferredHandler<'static>>,
+    {
+        self.state
+            .borrow_mut()
+            .methods
+            .insert(name.to_string(), Rc::new(RefCell::new(handler.into())));
+    }
+
+    pub fn notification<NH>(&self, name: &str, handler: NH)
+    where
+        NH: Into<NotificationHandler<'static>>,
+    {
+        self.state
+            .borrow_mut()
+            .notifications
+            .insert(name.to_string(), Rc::new(RefCell::new(handler.into())));
+    }
+
+    pub fn on_disconnect<DCB>(&self, disconnect_cb: DCB)
+    where
+        DCB: Into<DisconnectCallback<'static>>,
+    {
+        self.state.borrow_mut().disconnect_cb = Some(disconnect_cb.into());
+    }
+
+    pub fn start(&self) -> Result<(), Errno> {
+        let peer = self.clone();
+        self.state.borrow_mut().reader.read_start(
+            move |_: &StreamHandle, frame: Result<Vec<u8>, Errno>| match frame {
+                Ok(frame) => peer.on_frame(&frame),
+                Err(err) => peer.disconnect(err),
+            },
+        )
+    }
+
+    pub fn stop(&self) {
+        self.state.borrow_mut().reader.read_stop();
+    }
+
+    pub fn request<RCB>(
+        &self,
+        method: &str,
+        params: Value,
+        response_cb: RCB,
+    ) -> Result<RequestId, Errno>
+    where
+        RCB: Into<ResponseCallback<'static>>,
+    {
+        let id = {
+            let mut state = self.state.borrow_mut();
+            state.next_id += 1;
+            let id = RequestId::Number(state.next_id);
+            state.pending.insert(id.clone(), response_cb.into());
+            id
+        };
+
+        let request = Message::Request {
+            id: id.clone(),
+            method: method.to_string(),
+            params,
+        };
+        if let Err(err) = self.send(&request) {
+            self.state.borrow_mut().pending.remove(&id);
+            return Err(err);
+        }
+        Ok(id)
+    }
+
+    pub fn request_async(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Completion<Result<Value, RpcError>> {
+        let (completer, completion) = completion();
+        let on_response = completer.clone();
+        if let Err(err) = self.request(
+            method,
+            params,
+            move |_: &RpcPeer, result: Result<Value, RpcError>| on_response.complete(result),
+        ) {
+            completer.complete(Err(err.into()));
+        }
+        completion
+    }
+
+    pub fn notify(&self, method: &str, params: Value) -> Result<(), Errno> {
+        self.send(&Message::Notification {
+            method: method.to_string(),
+            params,
+        })
+    }
+
+    // NOTE: the response callback runs right away with REQUEST_CANCELLED, a late response is dropped
+    pub fn cancel(&self, id: &RequestId) -> Result<(), Errno> {
+        let response_cb = self.state.borrow_mut().pending.remove(id);
+        let Some(mut response_cb) = response_cb else {
+            return Ok(());
+        };
+
+        let result = self.notify(CANCEL_REQUEST, json!({ "id": id.to_value() }));
+        response_cb.0(self, Err(RpcError::request_cancelled()));
+        result
+    }
+
+    fn send(&self, message: &Message) -> Result<(), Errno> {
+        self.send_value(&message.to_value())
+    }
+
+    fn send_value(&self, value: &Value) -> Result<(), Errno> {
+        let frame = serde_json::to_vec(value).map_err(|_| Errno::EINVAL)?;
+        self.state.borrow_mut().writer.send(frame, ())
+    }
+
+    fn on_frame(&self, frame: &[u8]) {
+        // newline framing lets keep-alive blank lines through
+        if frame.iter().all(u8::is_ascii_whitespace) {
+            return;
+        }
+
+        let value = match serde_json::from_slice::<Value>(frame) {
+            Ok(value) => value,
+            Err(_) => {
+                let _ = self.send(&Message::Response {
+                    id: None,
+                    result: Err(RpcError::parse_error()),
+                });
+                return;
+            }
+        };
+
+        match value {
+            Value::Array(items) if items.is_empty() => {
+                let _ = self.send(&Message::Response {
+                    id: None,
+                    result: Err(RpcError::invalid_request()),
+                });
+            }
+            Value::Array(items) => {
+                // the extra count holds the batch open until every item has been dispatched
+                let batch = Rc::new(RefCell::new(Batch {
+                    remaining: 1,
+                    responses: Vec::new(),
+                }));
+                for item in &items {
+                    self.dispatch(item, Some(&batch));
+                }
+                let _ = self.release(&batch);
+            }
+            value => self.dispatch(&value, None),
+        }
+    }
+
+    fn dispatch(&self, value: &Value, batch: Option<&Rc<RefCell<Batch>>>) {
+        match Message::from_value(value) {
+            Ok(Message::Request { id, method, params }) => {
+                self.on_request(id, &method, params, batch)
+            }
+            Ok(Message::Notification { method, params }) => self.on_notification(&method, params),
+            Ok(Message::Response { id, result }) => self.on_response(id, result),
+            Err(error) => {
+                let response = Message::Response {
+                    id: value.get("id").and_then(RequestId::from_value),
+                    result: Err(error),
+                };
+                let _ = match batch {
+                    Some(batch) => {
+                        batch.borrow_mut().responses.push(response.to_value());
+                        Ok(())
+                    }
+                    None => self.send(&response),
+                };
+            }
+        }
+    }
+
+    fn on_request(
+        &self,
+        id: RequestId,
+        method: &str,
+        params: Value,
+        batch: Option<&Rc<RefCell<Batch>>>,
+    ) {
+        if let Some(batch) = batch {
+            batch.borrow_mut().remaining += 1;
+        }
+        self.state.borrow_mut().incoming.insert(id.clone(), false);
+
+        let handler = self.state.borrow().methods.get(method).cloned();
+        let responder = Responder {
+            peer: self.clone(),
+            id,
+            batch: batch.cloned(),
+            responded: false,
+        };
+        match handler {
+            Some(handler) => handler.borrow_mut().0(self, params, responder),
+            None => {
+                let _ = responder.respond(Err(RpcError::method_not_found(method)));
+            }
+        }
+    }
+
+    fn on_notification(&self, method: &str, params: Value) {
+        if method == CANCEL_REQUEST {
+            if let Some(id) = params.get("id").and_then(RequestId::from_value) {
+                if let Some(cancelled) = self.state.borrow_mut().incoming.get_mut(&id) {
+                    *cancelled = true;
+                }
+            }
+        }
+
+        let handler = self.state.borrow().notifications.get(method).cloned();
+        if let Some(handler) = handler {
+            handler.borrow_mut().0(self, params);
+        }
+    }
+
+    fn on_response(&self, id: Option<RequestId>, result: Result<Value, RpcError>) {
+        let Some(id) = id else {
+            return;
+        };
+
+        let response_cb = self.state.borrow_mut().pending.remove(&id);
+        if let Some(mut response_cb) = response_cb {
+            response_cb.0(self, result);
+        }
+    }
+
+    fn respond(
+        &self,
+        id: &RequestId,
+        batch: Option<&Rc<RefCell<Batch>>>,
+        result: Result<Value, RpcError>,
+    ) -> Result<(), Errno> {
+        let cancelled = self.state.borrow_mut().incoming.remove(id).unwrap_or(false);
+        let response = Message::Response {
+            id: Some(id.clone()),
+            result: if cancelled {
+                Err(RpcError::request_cancelled())
+            } else {
+                result
+            },
+        };
+
+        match batch {
+            Some(batch) => {
+                batch.borrow_mut().responses.push(response.to_value());
+                self.release(batch)
+            }
+            None => self.send(&response),
+        }
+    }
+
+    fn release(&self, batch: &Rc<RefCell<Batch>>) -> Result<(), Errno> {
+        let responses = {
+            let mut batch = batch.borrow_mut();
+            batch.remaining -= 1;
+            if batch.remaining > 0 || batch.responses.is_empty() {
+                return Ok(());
+            }
+            take(&mut batch.responses)
+        };
+        self.send_value(&Value::Array(responses))
+    }
+
+    fn disconnect(&self, err: Errno) {
+        self.stop();
+
+        let pending: Vec<_> = self.state.borrow_mut().pending.drain().collect();
+        for (_, mut response_cb) in pending {
+            response_cb.0(self, Err(RpcError::internal_error(&err.to_string())));
+        }
+
+        let disconnect_cb = self.state.borrow_mut().disconnect_cb.take();
+        if let Some(mut disconnect_cb) = disconnect_cb {
+            disconnect_cb.0(self, err);
+            self.state
+                .borrow_mut()
+                .disconnect_cb
+                .get_or_insert(disconnect_cb);
+        }
+    }
+}
+
+// trait
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        if !self.responded {
+            let _ = self.peer.respond(
+                &self.id,
+                self.batch.as_ref(),
+                Err(RpcError::internal_error(
+                    "request dropped without a response",
+                )),
+            );
+        }
+    }
+}
+
+impl<'a, Fn> From<Fn> for MethodHandler<'a>
+where
+    Fn: FnMut(&RpcPeer, Value) -> Result<Value, RpcError> + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a, Fn> From<Fn> for DeferredHandler<'a>
+where
+    Fn: FnMut(&RpcPeer, Value, Responder) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a, Fn> From<Fn> for NotificationHandler<'a>
+where
+    Fn: FnMut(&RpcPeer, Value) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a, Fn> From<Fn> for ResponseCallback<'a>
+where
+    Fn: FnMut(&RpcPeer, Result<Value, RpcError>) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for ResponseCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
+
+impl<'a, Fn> From<Fn> for DisconnectCallback<'a>
+where
+    Fn: FnMut(&RpcPeer, Errno) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for DisconnectCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
@@ -1,8 +1,12 @@
-use std::{collections::VecDeque, fmt::Debug};
+use std::{collections::VecDeque, fmt::Debug, mem::take, str::from_utf8};
 
-use crate::uv::Buf;
+use crate::{
+    tea::{Message, MouseEvent},
+    uv::Buf,
+};
 
-#[derive(Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KeyName {
     BEL,
     BS,
@@ -14,22 +18,94 @@ pub enum KeyName {
     ESC,
     DEL,
 
+    UP,
+    DOWN,
+    RIGHT,
+    LEFT,
+    BEGIN,
+    HOME,
+    END,
+    INSERT,
+    DELETE,
+    PAGE_UP,
+    PAGE_DOWN,
+
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+
+    // cursor position report, ESC [ row ; col R
+    CPR,
+
+    // bytes that are not valid UTF-8, kept in code
+    INVALID,
+
     NONE,
 }
 
+// NOTE: repeat and release are only reported under the kitty keyboard protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEventKind {
+    PRESS,
+    REPEAT,
+    RELEASE,
+}
+
 #[derive(Debug, Clone)]
 pub struct KeyCode {
     pub key: KeyName,
+    pub kind: KeyEventKind,
     pub code: Vec<u8>,
+    // the character typed, set for printable input only
+    pub char: Option<char>,
     pub shift: bool,
     pub ctrl: bool,
     pub alt: bool,
+    pub super_key: bool,
+    pub hyper: bool,
+    pub meta: bool,
+    pub caps_lock: bool,
+    pub num_lock: bool,
 }
 
 #[derive(Debug)]
 pub struct KeyCodeParser {
     buf: VecDeque<u8>,
     incomplete: Vec<u8>,
+    // the text of a bracketed paste still waiting on its closing marker
+    paste: Option<Vec<u8>>,
+}
+
+// NOTE: ESC [ private? params intermediates final, params split on ';' and sub-params on ':'
+#[derive(Debug, Default)]
+struct ControlSequence {
+    private: Option<u8>,
+    params: Vec<Vec<u32>>,
+    intermediates: Vec<u8>,
+    r#final: u8,
+}
+
+enum Scan<T> {
+    Complete(T),
+    Incomplete,
+    Invalid,
 }
 
 pub fn is_shift(ch: u8) -> bool {
@@ -43,147 +119,591 @@ pub fn is_shift(ch: u8) -> bool {
         || (ch >= b'\x7b' && ch <= b'\x7e');
 }
 
+const MODIFIER_SHIFT: u32 = 0b00000001;
+const MODIFIER_ALT: u32 = 0b00000010;
+const MODIFIER_CTRL: u32 = 0b00000100;
+const MODIFIER_SUPER: u32 = 0b00001000;
+const MODIFIER_HYPER: u32 = 0b00010000;
+const MODIFIER_META: u32 = 0b00100000;
+const MODIFIER_CAPS_LOCK: u32 = 0b01000000;
+const MODIFIER_NUM_LOCK: u32 = 0b10000000;
+
+// a bracketed paste opens with ESC [ 200 ~ and closes with ESC [ 201 ~
+const PASTE_START: u32 = 200;
+const PASTE_END: &[u8] = b"\x1b[201~";
+// longer pastes are handed over in chunks of this size
+pub const MAX_PASTE_LENGTH: usize = 1024 * 1024;
+
+// the key code xterm puts first in ESC [ 27 ; modifiers ; key ~ under modifyOtherKeys
+const MODIFY_OTHER_KEYS: u32 = 27;
+
+fn control(key: KeyName, ch: u8) -> KeyCode {
+    KeyCode {
+        ctrl: true,
+        ..KeyCode::new(key, vec![ch])
+    }
+}
+
+fn printable(ch: u8) -> KeyCode {
+    KeyCode {
+        char: Some(ch as char),
+        shift: is_shift(ch),
+        ..KeyCode::new(KeyName::NONE, vec![ch])
+    }
+}
+
+fn single_byte(ch: u8) -> KeyCode {
+    match ch {
+        b'\x07' => control(KeyName::BEL, ch),
+        b'\x08' => control(KeyName::BS, ch),
+        b'\t' => control(KeyName::HT, ch),
+        b'\n' => control(KeyName::LF, ch),
+        b'\x0b' => control(KeyName::VT, ch),
+        b'\x0c' => control(KeyName::FF, ch),
+        b'\r' => control(KeyName::CR, ch),
+        b'\x1b' => control(KeyName::ESC, ch),
+        b'\x7f' => control(KeyName::DEL, ch),
+        b'\x00'..=b'\x1f' => control(KeyName::NONE, ch),
+        _ => printable(ch),
+    }
+}
+
+// the length of a UTF-8 sequence from its lead byte, None for bytes that cannot start one
+fn utf8_len(ch: u8) -> Option<usize> {
+    match ch {
+        b'\xc2'..=b'\xdf' => Some(2),
+        b'\xe0'..=b'\xef' => Some(3),
+        b'\xf0'..=b'\xf4' => Some(4),
+        _ => None,
+    }
+}
+
+// NOTE: a character cut by the chunk boundary stays behind for the next chunk
+fn paste_chunk(paste: &mut Vec<u8>) -> String {
+    let end = match from_utf8(paste) {
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        _ => paste.len(),
+    };
+    let rest = paste.split_off(end);
+    let text = String::from_utf8_lossy(paste).into_owned();
+    *paste = rest;
+    text
+}
+
+// final bytes shared by CSI and SS3: cursor keys, Home/End/Begin and F1-F4
+fn letter_key(ch: u8) -> Option<KeyName> {
+    match ch {
+        b'A' => Some(KeyName::UP),
+        b'B' => Some(KeyName::DOWN),
+        b'C' => Some(KeyName::RIGHT),
+        b'D' => Some(KeyName::LEFT),
+        b'E' => Some(KeyName::BEGIN),
+        b'F' => Some(KeyName::END),
+        b'H' => Some(KeyName::HOME),
+        b'P' => Some(KeyName::F1),
+        b'Q' => Some(KeyName::F2),
+        b'R' => Some(KeyName::F3),
+        b'S' => Some(KeyName::F4),
+        _ => None,
+    }
+}
+
+// ESC [ n ~ as sent by xterm, 7 and 8 are the rxvt spellings of Home and End
+fn tilde_key(n: u32) -> Option<KeyName> {
+    match n {
+        1 | 7 => Some(KeyName::HOME),
+        2 => Some(KeyName::INSERT),
+        3 => Some(KeyName::DELETE),
+        4 | 8 => Some(KeyName::END),
+        5 => Some(KeyName::PAGE_UP),
+        6 => Some(KeyName::PAGE_DOWN),
+        11 => Some(KeyName::F1),
+        12 => Some(KeyName::F2),
+        13 => Some(KeyName::F3),
+        14 => Some(KeyName::F4),
+        15 => Some(KeyName::F5),
+        17 => Some(KeyName::F6),
+        18 => Some(KeyName::F7),
+        19 => Some(KeyName::F8),
+        20 => Some(KeyName::F9),
+        21 => Some(KeyName::F10),
+        23 => Some(KeyName::F11),
+        24 => Some(KeyName::F12),
+        25 => Some(KeyName::F13),
+        26 => Some(KeyName::F14),
+        28 => Some(KeyName::F15),
+        29 => Some(KeyName::F16),
+        31 => Some(KeyName::F17),
+        32 => Some(KeyName::F18),
+        33 => Some(KeyName::F19),
+        34 => Some(KeyName::F20),
+        _ => None,
+    }
+}
+
+// the kitty protocol reports keys without a character as codepoints in the private use area
+fn functional_key(code: u32) -> Option<KeyName> {
+    match code {
+        8 => Some(KeyName::BS),
+        9 => Some(KeyName::HT),
+        13 => Some(KeyName::CR),
+        27 => Some(KeyName::ESC),
+        127 => Some(KeyName::DEL),
+        57376 => Some(KeyName::F13),
+        57377 => Some(KeyName::F14),
+        57378 => Some(KeyName::F15),
+        57379 => Some(KeyName::F16),
+        57380 => Some(KeyName::F17),
+        57381 => Some(KeyName::F18),
+        57382 => Some(KeyName::F19),
+        57383 => Some(KeyName::F20),
+        57414 => Some(KeyName::CR),
+        57417 => Some(KeyName::LEFT),
+        57418 => Some(KeyName::RIGHT),
+        57419 => Some(KeyName::UP),
+        57420 => Some(KeyName::DOWN),
+        57421 => Some(KeyName::PAGE_UP),
+        57422 => Some(KeyName::PAGE_DOWN),
+        57423 => Some(KeyName::HOME),
+        57424 => Some(KeyName::END),
+        57425 => Some(KeyName::INSERT),
+        57426 => Some(KeyName::DELETE),
+        57427 => Some(KeyName::BEGIN),
+        // modifier, lock and media keys have no name of their own
+        57344..=57454 => Some(KeyName::NONE),
+        _ => None,
+    }
+}
+
+// kitty keypad keys that print, KP_0 through KP_EQUAL
+fn keypad_codepoint(code: u32) -> Option<char> {
+    match code {
+        57399..=57408 => char::from_digit(code - 57399, 10),
+        57409 => Some('.'),
+        57410 => Some('/'),
+        57411 => Some('*'),
+        57412 => Some('-'),
+        57413 => Some('+'),
+        57415 => Some('='),
+        57416 => Some(','),
+        _ => None,
+    }
+}
+
+// SS3 keypad keys in application mode, reported as the character they print
+fn keypad_char(ch: u8) -> Option<u8> {
+    match ch {
+        b'j' => Some(b'*'),
+        b'k' => Some(b'+'),
+        b'l' => Some(b','),
+        b'm' => Some(b'-'),
+        b'n' => Some(b'.'),
+        b'o' => Some(b'/'),
+        b'p'..=b'y' => Some(ch - b'p' + b'0'),
+        b'X' => Some(b'='),
+        _ => None,
+    }
+}
+
+impl ControlSequence {
+    // NOTE: a missing or zero parameter takes the default, as in ECMA-48
+    fn param(&self, index: usize, default: u32) -> u32 {
+        match self.params.get(index).and_then(|param| param.first()) {
+            Some(&value) if value != 0 => value,
+            _ => default,
+        }
+    }
+
+    fn sub_param(&self, index: usize, sub: usize) -> Option<u32> {
+        self.params.get(index)?.get(sub).copied()
+    }
+
+    fn is_plain(&self) -> bool {
+        self.private.is_none() && self.intermediates.is_empty()
+    }
+
+    // the kitty protocol appends the event type to the modifiers, 1 press, 2 repeat, 3 release
+    fn kind(&self) -> KeyEventKind {
+        match self.sub_param(1, 1) {
+            Some(2) => KeyEventKind::REPEAT,
+            Some(3) => KeyEventKind::RELEASE,
+            _ => KeyEventKind::PRESS,
+        }
+    }
+}
+
+impl KeyCode {
+    pub fn new(key: KeyName, code: Vec<u8>) -> Self {
+        Self {
+            key,
+            kind: KeyEventKind::PRESS,
+            code,
+            char: None,
+            shift: false,
+            ctrl: false,
+            alt: false,
+            super_key: false,
+            hyper: false,
+            meta: false,
+            caps_lock: false,
+            num_lock: false,
+        }
+    }
+}
+
 impl KeyCodeParser {
-    pub fn parse_keycode(&mut self) -> Option<KeyCode> {
+    // NOTE: keys come back as Message::Keypress, terminal reports as the message they stand for
+    pub fn parse_message(&mut self) -> Option<Message> {
         self.incomplete.clear();
+        if self.paste.is_some() {
+            return self.parse_paste();
+        }
 
-        let ch = self.advance();
-        while ch != 0 {
-            match ch {
-                b'\x07' => {
-                    return Some(KeyCode {
-                        key: KeyName::BEL,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+        let message = match self.advance() {
+            Some(b'\x1b') => self.parse_escape(),
+            Some(ch @ b'\x80'..=b'\xff') => self.parse_utf8(ch).map(Message::Keypress),
+            Some(ch) => Some(Message::Keypress(single_byte(ch))),
+            None => None,
+        };
+        if message.is_none() {
+            // not enough input yet, keep the bytes for the next read
+            while let Some(ch) = self.incomplete.pop() {
+                self.buf.push_front(ch);
+            }
+        }
+        message
+    }
+
+    // NOTE: for callers that only want keys, the other messages are read and dropped
+    pub fn parse_keycode(&mut self) -> Option<KeyCode> {
+        loop {
+            if let Message::Keypress(keycode) = self.parse_message()? {
+                return Some(keycode);
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Option<Message> {
+        let keycode = match *self.buf.front()? {
+            b'[' => {
+                self.advance();
+                match self.parse_csi() {
+                    Scan::Complete(sequence) if sequence.r#final == b'[' => self.parse_linux()?,
+                    Scan::Complete(sequence)
+                        if sequence.is_plain()
+                            && sequence.r#final == b'~'
+                            && sequence.param(0, 0) == PASTE_START =>
+                    {
+                        // the opening marker is consumed even while the paste is still arriving
+                        self.incomplete.clear();
+                        self.paste = Some(Vec::new());
+                        return self.parse_paste();
+                    }
+                    Scan::Complete(sequence) => return Some(self.csi_message(&sequence)),
+                    Scan::Incomplete => return None,
+                    Scan::Invalid => self.keycode(KeyName::NONE, 1),
                 }
-                b'\x08' => {
-                    return Some(KeyCode {
-                        key: KeyName::BS,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+            }
+            b'O' => {
+                self.advance();
+                self.parse_ss3()?
+            }
+            // ESC ESC is two presses of Escape, the second may still start a sequence
+            b'\x1b' => self.keycode(KeyName::ESC, 1),
+            _ => {
+                // NOTE: terminals send Alt+key as ESC followed by the key
+                let keycode = match self.advance()? {
+                    ch @ b'\x80'..=b'\xff' => self.parse_utf8(ch)?,
+                    ch => single_byte(ch),
+                };
+                KeyCode {
+                    code: self.incomplete.clone(),
+                    alt: true,
+                    ..keycode
                 }
-                b'\t' => {
-                    return Some(KeyCode {
-                        key: KeyName::HT,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+            }
+        };
+        Some(Message::Keypress(keycode))
+    }
+
+    // NOTE: called once the escape timeout passes, settles whatever parse_message is waiting on
+    pub fn flush(&mut self) -> Option<Message> {
+        if let Some(message) = self.parse_message() {
+            return Some(message);
+        }
+        // the closing marker never came, hand over what arrived and read the rest as keys
+        if let Some(text) = self.paste.take() {
+            return Some(Message::Paste(String::from_utf8_lossy(&text).into_owned()));
+        }
+
+        let keycode = match self.advance()? {
+            b'\x1b' => match self.buf.front() {
+                // a sequence that never finished was Alt+[ or Alt+O, the rest is read as typed
+                Some(b'[' | b'O') => {
+                    let ch = self.advance()?;
+                    KeyCode {
+                        code: self.incomplete.clone(),
+                        alt: true,
+                        ..printable(ch)
+                    }
                 }
-                b'\n' => {
-                    return Some(KeyCode {
-                        key: KeyName::LF,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+                _ => self.keycode(KeyName::ESC, 1),
+            },
+            // the rest of a UTF-8 sequence never arrived
+            _ => {
+                while let Some(b'\x80'..=b'\xbf') = self.buf.front() {
+                    self.advance();
                 }
-                b'\x0b' => {
-                    return Some(KeyCode {
-                        key: KeyName::VT,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+                self.keycode(KeyName::INVALID, 1)
+            }
+        };
+        Some(Message::Keypress(keycode))
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.paste.is_some() || !self.buf.is_empty()
+    }
+
+    pub fn is_pasting(&self) -> bool {
+        self.paste.is_some()
+    }
+
+    // NOTE: the pasted bytes are taken as they are, an ESC inside only matters if it closes the paste
+    fn parse_paste(&mut self) -> Option<Message> {
+        let paste = self.paste.as_mut()?;
+        loop {
+            if self.buf.front()? == &b'\x1b' {
+                let matched = self
+                    .buf
+                    .iter()
+                    .zip(PASTE_END)
+                    .take_while(|(ch, end)| ch == end)
+                    .count();
+                if matched == PASTE_END.len() {
+                    self.buf.drain(..matched);
+                    let text = self.paste.take().unwrap_or_default();
+                    return Some(Message::Paste(String::from_utf8_lossy(&text).into_owned()));
                 }
-                b'\x0c' => {
-                    return Some(KeyCode {
-                        key: KeyName::FF,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+                if matched == self.buf.len() {
+                    // the closing marker may be split across reads
+                    return None;
                 }
-                b'\r' => {
-                    return Some(KeyCode {
-                        key: KeyName::CR,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+            }
+            if paste.len() >= MAX_PASTE_LENGTH {
+                return Some(Message::Paste(paste_chunk(paste)));
+            }
+            paste.extend(self.buf.pop_front());
+        }
+    }
+
+    // NOTE: a sequence split across reads waits for the rest, one broken by a new lead byte is INVALID
+    fn parse_utf8(&mut self, lead: u8) -> Option<KeyCode> {
+        let Some(len) = utf8_len(lead) else {
+            return Some(self.keycode(KeyName::INVALID, 1));
+        };
+
+        // the sequence may follow an ESC when Alt is held
+        let start = self.incomplete.len() - 1;
+        while self.incomplete.len() - start < len {
+            match self.advance()? {
+                b'\x80'..=b'\xbf' => {}
+                _ => {
+                    self.unadvance();
+                    return Some(self.keycode(KeyName::INVALID, 1));
                 }
-                b'\x1b' => {
-                    if !self.r#match(b'[') {
-                        break;
-                    }
-                    self.parse_num();
-                    if !self.r#match(b';') {
-                        break;
-                    }
-                    self.parse_num();
-                    if !self.r#match(b'R') {
-                        break;
-                    }
+            }
+        }
 
-                    let mut code = Vec::with_capacity(self.incomplete.len());
-                    for ch in self.incomplete.iter() {
-                        code.push(*ch);
-                    }
+        // overlong forms and surrogates pass the byte checks above but not from_utf8
+        match from_utf8(&self.incomplete[start..]) {
+            Ok(text) => Some(KeyCode {
+                char: text.chars().next(),
+                ..self.keycode(KeyName::NONE, 1)
+            }),
+            Err(_) => Some(self.keycode(KeyName::INVALID, 1)),
+        }
+    }
 
-                    return Some(KeyCode {
-                        key: KeyName::NONE,
-                        code: code,
-                        shift: false,
-                        ctrl: false,
-                        alt: false,
-                    });
+    fn parse_csi(&mut self) -> Scan<ControlSequence> {
+        let mut sequence = ControlSequence::default();
+        if let Some(&ch @ b'<'..=b'?') = self.buf.front() {
+            self.advance();
+            sequence.private = Some(ch);
+        }
+
+        let mut param = Vec::new();
+        let mut value: Option<u32> = None;
+        loop {
+            let Some(ch) = self.advance() else {
+                return Scan::Incomplete;
+            };
+            match ch {
+                b'0'..=b'9' => {
+                    let digit = (ch - b'0') as u32;
+                    value = Some(value.unwrap_or(0).saturating_mul(10).saturating_add(digit));
                 }
-                b'\x7f' => {
-                    return Some(KeyCode {
-                        key: KeyName::DEL,
-                        code: vec![ch],
-                        shift: false,
-                        ctrl: true,
-                        alt: false,
-                    });
+                b':' => param.push(value.take().unwrap_or(0)),
+                b';' => {
+                    param.push(value.take().unwrap_or(0));
+                    sequence.params.push(take(&mut param));
                 }
-                _ => {
-                    if ch < b'\x20' {
-                        return Some(KeyCode {
-                            key: KeyName::NONE,
-                            code: vec![ch],
-                            shift: false,
-                            ctrl: true,
-                            alt: false,
-                        });
-                    }
-
-                    if ch >= b'\x20' && ch <= b'\x7e' {
-                        return Some(KeyCode {
-                            key: KeyName::NONE,
-                            code: vec![ch],
-                            shift: is_shift(ch),
-                            ctrl: false,
-                            alt: false,
-                        });
+                b' '..=b'/' => sequence.intermediates.push(ch),
+                b'@'..=b'~' => {
+                    if value.is_some() || !param.is_empty() || !sequence.params.is_empty() {
+                        param.push(value.unwrap_or(0));
+                        sequence.params.push(param);
                     }
+                    sequence.r#final = ch;
+                    return Scan::Complete(sequence);
+                }
+                _ => {
+                    // a byte that cannot be part of the sequence starts the next key
+                    self.unadvance();
+                    return Scan::Invalid;
                 }
             }
         }
+    }
+
+    // the linux console sends ESC [ [ A through ESC [ [ E for F1-F5
+    fn parse_linux(&mut self) -> Option<KeyCode> {
+        let key = match self.advance()? {
+            b'A' => KeyName::F1,
+            b'B' => KeyName::F2,
+            b'C' => KeyName::F3,
+            b'D' => KeyName::F4,
+            b'E' => KeyName::F5,
+            _ => KeyName::NONE,
+        };
+        Some(self.keycode(key, 1))
+    }
 
-        for _ in 0..self.incomplete.len() {
-            self.buf.push_front(self.incomplete.pop().unwrap());
+    fn parse_ss3(&mut self) -> Option<KeyCode> {
+        // older xterms put the modifier between O and the final byte, ESC O 5 A
+        let mut modifiers = 0u32;
+        loop {
+            let ch = self.advance()?;
+            if ch.is_ascii_digit() {
+                modifiers = modifiers
+                    .saturating_mul(10)
+                    .saturating_add((ch - b'0') as u32);
+                continue;
+            }
+
+            if let Some(key) = letter_key(ch) {
+                return Some(self.keycode(key, modifiers.max(1)));
+            }
+            return Some(match (ch, keypad_char(ch)) {
+                (b'M', _) => KeyCode {
+                    code: self.incomplete.clone(),
+                    ..control(KeyName::CR, b'\r')
+                },
+                (_, Some(ch)) => printable(ch),
+                (_, None) => self.keycode(KeyName::NONE, 1),
+            });
         }
-        None
     }
 
-    fn parse_num(&mut self) {
-        while let Some(ch) = self.buf.front() {
-            if ch <= &b'0' || ch >= &b'9' {
-                break;
+    fn csi_message(&self, sequence: &ControlSequence) -> Message {
+        match (sequence.private, sequence.r#final) {
+            (Some(b'<'), b'M' | b'm') if sequence.intermediates.is_empty() => {
+                Message::Mouse(MouseEvent::from_sgr(
+                    sequence.param(0, 0),
+                    sequence.param(1, 1),
+                    sequence.param(2, 1),
+                    sequence.r#final == b'm',
+                ))
             }
-            self.advance();
+            // focus reports, ESC [ I when the terminal gains focus and ESC [ O when it loses it
+            (None, b'I' | b'O') if sequence.params.is_empty() && sequence.is_plain() => {
+                Message::Focus(sequence.r#final == b'I')
+            }
+            _ => Message::Keypress(self.csi_keycode(sequence)),
+        }
+    }
+
+    fn csi_keycode(&self, sequence: &ControlSequence) -> KeyCode {
+        if !sequence.is_plain() {
+            return self.keycode(KeyName::NONE, 1);
+        }
+
+        // NOTE: xterm also reports Ctrl+F3 as ESC [ 1 ; 5 R, two parameters are read as a CPR
+        let keycode = match sequence.r#final {
+            b'R' if sequence.params.len() == 2 => self.keycode(KeyName::CPR, 1),
+            b'Z' => self.keycode(KeyName::HT, MODIFIER_SHIFT + 1),
+            b'u' => self.codepoint_keycode(sequence, 0, 1),
+            b'~' if sequence.param(0, 0) == MODIFY_OTHER_KEYS => {
+                self.codepoint_keycode(sequence, 2, 1)
+            }
+            b'~' => match tilde_key(sequence.param(0, 0)) {
+                Some(key) => self.keycode(key, sequence.param(1, 1)),
+                None => self.keycode(KeyName::NONE, 1),
+            },
+            ch => match letter_key(ch) {
+                Some(key) => self.keycode(key, sequence.param(1, 1)),
+                None => self.keycode(KeyName::NONE, 1),
+            },
+        };
+        KeyCode {
+            kind: sequence.kind(),
+            ..keycode
+        }
+    }
+
+    // NOTE: kitty sends ESC [ key : shifted ; modifiers : event ; text u,
+    // xterm modifyOtherKeys sends ESC [ 27 ; modifiers ; key ~
+    fn codepoint_keycode(
+        &self,
+        sequence: &ControlSequence,
+        key: usize,
+        modifiers: usize,
+    ) -> KeyCode {
+        let code = sequence.param(key, 0);
+        let keycode = self.keycode(KeyName::NONE, sequence.param(modifiers, 1));
+        if let Some(ch) = keypad_codepoint(code) {
+            return KeyCode {
+                char: Some(ch),
+                ..keycode
+            };
+        }
+        if let Some(key) = functional_key(code) {
+            return KeyCode { key, ..keycode };
+        }
+
+        // the text the key produced when the terminal reports it, otherwise the shifted key
+        let text: Option<String> = sequence
+            .params
+            .get(2)
+            .filter(|_| sequence.r#final == b'u')
+            .map(|text| text.iter().filter_map(|&ch| char::from_u32(ch)).collect());
+        let char = match text.and_then(|text| text.chars().next()) {
+            Some(ch) => Some(ch),
+            None if keycode.shift => sequence
+                .sub_param(key, 1)
+                .filter(|_| sequence.r#final == b'u')
+                .and_then(char::from_u32)
+                .or_else(|| char::from_u32(code).map(|ch| ch.to_ascii_uppercase())),
+            None => char::from_u32(code),
+        };
+        KeyCode {
+            char: char.filter(|ch| !ch.is_control()),
+            ..keycode
+        }
+    }
+
+    // NOTE: xterm and kitty encode modifiers as 1 + bits, shift 1, alt 2, ctrl 4, super 8
+    fn keycode(&self, key: KeyName, modifiers: u32) -> KeyCode {
+        let modifiers = modifiers.saturating_sub(1);
+        KeyCode {
+            shift: modifiers & MODIFIER_SHIFT != 0,
+            ctrl: modifiers & MODIFIER_CTRL != 0,
+            alt: modifiers & MODIFIER_ALT != 0,
+            super_key: modifiers & MODIFIER_SUPER != 0,
+            hyper: modifiers & MODIFIER_HYPER != 0,
+            meta: modifiers & MODIFIER_META != 0,
+            caps_lock: modifiers & MODIFIER_CAPS_LOCK != 0,
+            num_lock: modifiers & MODIFIER_NUM_LOCK != 0,
+            ..KeyCode::new(key, self.incomplete.clone())
         }
     }
 
@@ -191,21 +711,15 @@ impl KeyCodeParser {
         self.buf.extend(buf.as_ref()[..buf.len() - 1].iter()); // remove null terminator
     }
 
-    fn r#match(&mut self, expect: u8) -> bool {
-        if self.advance() != expect {
-            false
-        } else {
-            true
-        }
+    fn advance(&mut self) -> Option<u8> {
+        let ch = self.buf.pop_front()?;
+        self.incomplete.push(ch);
+        Some(ch)
     }
 
-    fn advance(&mut self) -> u8 {
-        match self.buf.pop_front() {
-            Some(ch) => {
-                self.incomplete.push(ch);
-                ch
-            }
-            None => 0,
+    fn unadvance(&mut self) {
+        if let Some(ch) = self.incomplete.pop() {
+            self.buf.push_front(ch);
         }
     }
 }
@@ -215,6 +729,462 @@ impl Default for KeyCodeParser {
         Self {
             buf: Default::default(),
             incomplete: Default::default(),
+            paste: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&[u8]]) -> Vec<Message> {
+        let mut parser = KeyCodeParser::default();
+        let mut messages = Vec::new();
+        for chunk in chunks {
+            parser.buffer(&Buf::from(*chunk));
+            while let Some(message) = parser.parse_message() {
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
+    fn keypress_of(message: Message) -> KeyCode {
+        match message {
+            Message::Keypress(keycode) => keycode,
+            _ => panic!("expected a keypress"),
+        }
+    }
+
+    fn keypresses(chunks: &[&[u8]]) -> Vec<KeyCode> {
+        parse(chunks).into_iter().map(keypress_of).collect()
+    }
+
+    fn keypress(input: &[u8]) -> KeyCode {
+        let mut keys = keypresses(&[input]);
+        assert_eq!(keys.len(), 1, "{:?}", input);
+        keys.remove(0)
+    }
+
+    fn modifiers(keycode: &KeyCode) -> (bool, bool, bool) {
+        (keycode.shift, keycode.alt, keycode.ctrl)
+    }
+
+    #[test]
+    fn control_bytes() {
+        let keys = keypresses(&[b"B\x01\r\x7f"]);
+        assert_eq!(keys.len(), 4);
+        assert_eq!((keys[0].key, keys[0].shift), (KeyName::NONE, true));
+        assert_eq!((keys[1].key, keys[1].ctrl), (KeyName::NONE, true));
+        assert_eq!(keys[2].key, KeyName::CR);
+        assert_eq!(keys[3].key, KeyName::DEL);
+    }
+
+    #[test]
+    fn csi_modifiers() {
+        let up = keypress(b"\x1b[1;5A");
+        assert_eq!(
+            (up.key, modifiers(&up)),
+            (KeyName::UP, (false, false, true))
+        );
+        assert_eq!(up.code, b"\x1b[1;5A");
+
+        let left = keypress(b"\x1b[1;2D");
+        assert_eq!(
+            (left.key, modifiers(&left)),
+            (KeyName::LEFT, (true, false, false))
+        );
+
+        let delete = keypress(b"\x1b[3;3~");
+        assert_eq!(
+            (delete.key, modifiers(&delete)),
+            (KeyName::DELETE, (false, true, false))
+        );
+
+        let home = keypress(b"\x1b[1;8H");
+        assert_eq!(
+            (home.key, modifiers(&home)),
+            (KeyName::HOME, (true, true, true))
+        );
+
+        let f12 = keypress(b"\x1b[24~");
+        assert_eq!(
+            (f12.key, modifiers(&f12)),
+            (KeyName::F12, (false, false, false))
+        );
+
+        let backtab = keypress(b"\x1b[Z");
+        assert_eq!((backtab.key, backtab.shift), (KeyName::HT, true));
+    }
+
+    #[test]
+    fn ss3_modifiers() {
+        let f1 = keypress(b"\x1bOP");
+        assert_eq!(
+            (f1.key, modifiers(&f1)),
+            (KeyName::F1, (false, false, false))
+        );
+
+        assert_eq!(keypress(b"\x1bOA").key, KeyName::UP);
+        assert_eq!(keypress(b"\x1bOM").key, KeyName::CR);
+
+        let f3 = keypress(b"\x1bO5R");
+        assert_eq!(
+            (f3.key, modifiers(&f3)),
+            (KeyName::F3, (false, false, true))
+        );
+        assert_eq!(f3.code, b"\x1bO5R");
+    }
+
+    #[test]
+    fn split_reads() {
+        let mut parser = KeyCodeParser::default();
+        parser.buffer(&Buf::from(&b"\x1b[1;"[..]));
+        assert!(parser.parse_message().is_none());
+        parser.buffer(&Buf::from(&b"5A"[..]));
+        let up = parser.parse_message().map(keypress_of).expect("Ctrl+Up");
+        assert_eq!((up.key, up.ctrl), (KeyName::UP, true));
+        assert_eq!(up.code, b"\x1b[1;5A");
+        assert!(parser.parse_message().is_none());
+
+        let keys = keypresses(&[&b"\x1b"[..], b"[", b"2", b"4~x"]);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].key, KeyName::F12);
+        assert_eq!(keys[1].code, b"x");
+    }
+
+    #[test]
+    fn cpr_and_ctrl_f3() {
+        let cpr = keypress(b"\x1b[12;40R");
+        assert_eq!(cpr.key, KeyName::CPR);
+        assert_eq!(cpr.code, b"\x1b[12;40R");
+
+        // NOTE: ESC [ 1 ; 5 R is ambiguous and goes to the CPR, the SS3 form stays Ctrl+F3
+        assert_eq!(keypress(b"\x1b[1;5R").key, KeyName::CPR);
+        let f3 = keypress(b"\x1bO5R");
+        assert_eq!((f3.key, f3.ctrl), (KeyName::F3, true));
+
+        let f3 = keypress(b"\x1b[R");
+        assert_eq!((f3.key, f3.ctrl), (KeyName::F3, false));
+    }
+
+    #[test]
+    fn linux_console() {
+        let keys = keypresses(&[b"\x1b[[A\x1b[[E"]);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(
+            (keys[0].key, keys[0].code.as_slice()),
+            (KeyName::F1, &b"\x1b[[A"[..])
+        );
+        assert_eq!(keys[1].key, KeyName::F5);
+
+        let keys = keypresses(&[&b"\x1b["[..], b"[", b"C"]);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, KeyName::F3);
+    }
+
+    #[test]
+    fn utf8_multibyte() {
+        let keys = keypresses(&["aé€😀".as_bytes()]);
+        let chars: Vec<_> = keys.iter().map(|keycode| keycode.char).collect();
+        assert_eq!(chars, [Some('a'), Some('é'), Some('€'), Some('😀')]);
+        assert_eq!(keys[2].code, "€".as_bytes());
+        assert!(keys.iter().all(|keycode| keycode.key == KeyName::NONE));
+
+        assert_eq!(keypress(b"\r").char, None);
+    }
+
+    #[test]
+    fn utf8_split_reads() {
+        let keys = keypresses(&[&b"\xe2"[..], b"\x82", b"\xacx"]);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(
+            (keys[0].char, keys[0].code.as_slice()),
+            (Some('€'), &b"\xe2\x82\xac"[..])
+        );
+        assert_eq!(keys[1].char, Some('x'));
+    }
+
+    #[test]
+    fn utf8_invalid() {
+        let invalid = keypress(b"\xff");
+        assert_eq!((invalid.key, invalid.char), (KeyName::INVALID, None));
+        assert_eq!(invalid.code, b"\xff");
+
+        // a lead byte cut short by the next key gives that key back
+        let keys = keypresses(&[b"\xc3a"]);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(
+            (keys[0].key, keys[0].code.as_slice()),
+            (KeyName::INVALID, &b"\xc3"[..])
+        );
+        assert_eq!(keys[1].char, Some('a'));
+
+        // overlong forms and surrogates
+        let keys = keypresses(&[b"\xc0\x80"]);
+        assert!(keys.iter().all(|keycode| keycode.key == KeyName::INVALID));
+        let surrogate = keypress(b"\xed\xa0\x80");
+        assert_eq!(surrogate.key, KeyName::INVALID);
+        assert_eq!(surrogate.code, b"\xed\xa0\x80");
+    }
+
+    fn flushed(input: &[u8]) -> Vec<KeyCode> {
+        let mut parser = KeyCodeParser::default();
+        parser.buffer(&Buf::from(input));
+        let mut keys = Vec::new();
+        while let Some(message) = parser.parse_message() {
+            keys.push(keypress_of(message));
+        }
+        assert!(parser.is_pending(), "{:?}", input);
+        while let Some(message) = parser.flush() {
+            keys.push(keypress_of(message));
+        }
+        assert!(!parser.is_pending());
+        keys
+    }
+
+    #[test]
+    fn alt_keys() {
+        let alt_a = keypress(b"\x1ba");
+        assert_eq!(
+            (alt_a.key, alt_a.char, modifiers(&alt_a)),
+            (KeyName::NONE, Some('a'), (false, true, false))
+        );
+        assert_eq!(alt_a.code, b"\x1ba");
+
+        let alt_e = keypress("\x1bé".as_bytes());
+        assert_eq!((alt_e.char, alt_e.alt), (Some('é'), true));
+
+        let alt_enter = keypress(b"\x1b\r");
+        assert_eq!((alt_enter.key, alt_enter.alt), (KeyName::CR, true));
+    }
+
+    #[test]
+    fn lone_escape_waits_for_flush() {
+        let mut parser = KeyCodeParser::default();
+        parser.buffer(&Buf::from(&b"\x1b"[..]));
+        assert!(parser.parse_message().is_none());
+        assert!(parser.is_pending());
+        let escape = parser.flush().map(keypress_of).expect("Escape");
+        assert_eq!((escape.key, escape.alt), (KeyName::ESC, false));
+        assert!(parser.flush().is_none());
+
+        // ESC ESC is one Escape now and one that still waits
+        let keys = flushed(b"\x1b\x1b");
+        assert_eq!(keys.len(), 2);
+        assert!(keys.iter().all(|keycode| keycode.key == KeyName::ESC));
+    }
+
+    #[test]
+    fn stalled_sequences_flush() {
+        let keys = flushed(b"\x1b[");
+        assert_eq!(keys.len(), 1);
+        assert_eq!((keys[0].char, keys[0].alt), (Some('['), true));
+
+        let keys = flushed(b"\x1bO");
+        assert_eq!((keys[0].char, keys[0].alt), (Some('O'), true));
+
+        let keys = flushed(b"\xe2\x82");
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, KeyName::INVALID);
+    }
+
+    #[test]
+    fn kitty_codepoints() {
+        let ctrl_a = keypress(b"\x1b[97;5u");
+        assert_eq!(
+            (ctrl_a.key, ctrl_a.char, modifiers(&ctrl_a)),
+            (KeyName::NONE, Some('a'), (false, false, true))
+        );
+        assert_eq!(ctrl_a.kind, KeyEventKind::PRESS);
+
+        // the shifted key comes from the alternate when it is reported, from the key otherwise
+        assert_eq!(keypress(b"\x1b[97:65;2u").char, Some('A'));
+        assert_eq!(keypress(b"\x1b[97;2u").char, Some('A'));
+        assert_eq!(keypress(b"\x1b[97;1;228u").char, Some('ä'));
+
+        let super_a = keypress(b"\x1b[97;9u");
+        assert!(super_a.super_key && !super_a.ctrl);
+        let locked = keypress(b"\x1b[97;193u");
+        assert!(locked.caps_lock && locked.num_lock && !locked.shift);
+    }
+
+    #[test]
+    fn kitty_event_kinds() {
+        assert_eq!(keypress(b"\x1b[97;1:2u").kind, KeyEventKind::REPEAT);
+        assert_eq!(keypress(b"\x1b[97;1:3u").kind, KeyEventKind::RELEASE);
+
+        let up = keypress(b"\x1b[1;5:3A");
+        assert_eq!(
+            (up.key, up.ctrl, up.kind),
+            (KeyName::UP, true, KeyEventKind::RELEASE)
+        );
+    }
+
+    #[test]
+    fn kitty_functional_keys() {
+        assert_eq!(keypress(b"\x1b[13u").key, KeyName::CR);
+        assert_eq!(keypress(b"\x1b[27u").key, KeyName::ESC);
+        assert_eq!(keypress(b"\x1b[57376u").key, KeyName::F13);
+
+        let keypad = keypress(b"\x1b[57399u");
+        assert_eq!((keypad.key, keypad.char), (KeyName::NONE, Some('0')));
+
+        // left shift on its own has neither a name nor a character
+        let shift = keypress(b"\x1b[57441;2u");
+        assert_eq!((shift.key, shift.char), (KeyName::NONE, None));
+    }
+
+    #[test]
+    fn modify_other_keys() {
+        let ctrl_a = keypress(b"\x1b[27;5;97~");
+        assert_eq!(
+            (ctrl_a.char, modifiers(&ctrl_a)),
+            (Some('a'), (false, false, true))
+        );
+
+        let shift_enter = keypress(b"\x1b[27;2;13~");
+        assert_eq!((shift_enter.key, shift_enter.shift), (KeyName::CR, true));
+    }
+
+    #[test]
+    fn sgr_mouse() {
+        use crate::tea::{MouseAction, MouseButton};
+
+        let messages = parse(&[&b"\x1b[<0;10;5M\x1b[<0;10;5ma"[..]]);
+        assert_eq!(messages.len(), 3);
+        match messages[0] {
+            Message::Mouse(event) => {
+                assert_eq!(event.button, MouseButton::LEFT);
+                assert_eq!(event.action, MouseAction::PRESS);
+                assert_eq!((event.row, event.col), (5, 10));
+            }
+            _ => panic!("expected a mouse event"),
+        }
+        match messages[1] {
+            Message::Mouse(event) => assert_eq!(event.action, MouseAction::RELEASE),
+            _ => panic!("expected a mouse event"),
+        }
+        match &messages[2] {
+            Message::Keypress(keycode) => assert_eq!(keycode.char, Some('a')),
+            _ => panic!("expected a keypress"),
+        }
+    }
+
+    fn pastes(messages: &[Message]) -> Vec<&str> {
+        messages
+            .iter()
+            .filter_map(|message| match message {
+                Message::Paste(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bracketed_paste() {
+        let messages = parse(&[&b"\x1b[200~hello\r\nworld\x1b[201~"[..]]);
+        assert_eq!(pastes(&messages), ["hello\r\nworld"]);
+        assert_eq!(messages.len(), 1);
+
+        // an ESC inside the paste is kept as text
+        let messages = parse(&[&b"\x1b[200~a\x1b[Ab\x1b\x1b[201~"[..]]);
+        assert_eq!(pastes(&messages), ["a\x1b[Ab\x1b"]);
+    }
+
+    #[test]
+    fn paste_split_reads() {
+        let messages = parse(&[&b"\x1b[20"[..], b"0~caf\xc3", b"\xa9\x1b[2", b"01", b"~x"]);
+        assert_eq!(pastes(&messages), ["caf\u{e9}"]);
+        match &messages[1] {
+            Message::Keypress(keycode) => assert_eq!(keycode.char, Some('x')),
+            _ => panic!("expected a keypress after the paste"),
+        }
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn paste_waits_for_end() {
+        let mut parser = KeyCodeParser::default();
+        parser.buffer(&Buf::from(&b"\x1b[200~abc\x1b[20"[..]));
+        assert!(parser.parse_message().is_none());
+        assert!(parser.is_pending() && parser.is_pasting());
+        parser.buffer(&Buf::from(&b"1~"[..]));
+        match parser.parse_message() {
+            Some(Message::Paste(text)) => assert_eq!(text, "abc"),
+            _ => panic!("expected the paste"),
+        }
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn paste_flush_without_end() {
+        let mut parser = KeyCodeParser::default();
+        parser.buffer(&Buf::from(&b"\x1b[200~abc"[..]));
+        assert!(parser.parse_message().is_none());
+        match parser.flush() {
+            Some(Message::Paste(text)) => assert_eq!(text, "abc"),
+            _ => panic!("expected the partial paste"),
+        }
+        assert!(!parser.is_pasting());
+
+        // what follows a paste that went quiet is read as keys again
+        parser.buffer(&Buf::from(&b"x"[..]));
+        assert_eq!(
+            parser
+                .parse_message()
+                .map(keypress_of)
+                .map(|keycode| keycode.char),
+            Some(Some('x'))
+        );
+    }
+
+    #[test]
+    fn paste_chunks() {
+        let mut input = b"\x1b[200~".to_vec();
+        input.extend(vec![b'a'; MAX_PASTE_LENGTH]);
+        input.extend(b"b\x1b[201~");
+        let messages = parse(&[&input]);
+        let chunks = pastes(&messages);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), MAX_PASTE_LENGTH);
+        assert_eq!(chunks[1], "b");
+
+        // a character cut by the boundary moves whole into the next chunk
+        let mut input = b"\x1b[200~".to_vec();
+        input.extend(vec![b'a'; MAX_PASTE_LENGTH - 1]);
+        input.extend("\u{e9}b\x1b[201~".as_bytes());
+        let messages = parse(&[&input]);
+        let chunks = pastes(&messages);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), MAX_PASTE_LENGTH - 1);
+        assert_eq!(chunks[1], "\u{e9}b");
+    }
+
+    #[test]
+    fn focus_reports() {
+        let messages = parse(&[&b"\x1b[I\x1b[O"[..]]);
+        assert!(matches!(
+            messages[..],
+            [Message::Focus(true), Message::Focus(false)]
+        ));
+
+        // with a modifier the same finals are not focus reports
+        let messages = parse(&[&b"\x1b[1;5I"[..]]);
+        assert!(matches!(messages[..], [Message::Keypress(_)]));
+
+        // SS3 keys still decode while focus reports share the O final
+        let keys = keypresses(&[b"\x1bOP"]);
+        assert_eq!(keys[0].key, KeyName::F1);
+    }
+
+    #[test]
+    fn parse_keycode_skips_other_messages() {
+        let mut parser = KeyCodeParser::default();
+        parser.buffer(&Buf::from(&b"\x1b[I\x1b[<0;1;1M\x1b[12;40R"[..]));
+        let cpr = parser.parse_keycode().expect("the CPR");
+        assert_eq!(cpr.key, KeyName::CPR);
+        assert!(parser.parse_keycode().is_none());
+    }
+}
@@ -1,6 +1,6 @@
 use std::{error::Error, fmt::Debug};
 
-use crate::tea::KeyCode;
+use crate::tea::{KeyCode, MouseEvent};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MessageType {
@@ -8,6 +8,10 @@ pub enum MessageType {
     Interrupt = 1,
     Keypress = 2,
     Error = 3,
+    Mouse = 4,
+    Paste = 5,
+    Focus = 6,
+    Resize = 7,
 }
 
 pub enum Message {
@@ -15,6 +19,11 @@ pub enum Message {
     Interrupt,
     Keypress(KeyCode),
     Error(Box<dyn Error>),
+    Mouse(MouseEvent),
+    Paste(String),
+    // true when the terminal gained focus
+    Focus(bool),
+    Resize { width: i32, height: i32 },
 }
 
 impl Message {
@@ -24,6 +33,10 @@ impl Message {
             Self::Interrupt => MessageType::Interrupt,
             Self::Keypress(_) => MessageType::Keypress,
             Self::Error(_) => MessageType::Error,
+            Self::Mouse(_) => MessageType::Mouse,
+            Self::Paste(_) => MessageType::Paste,
+            Self::Focus(_) => MessageType::Focus,
+            Self::Resize { .. } => MessageType::Resize,
         }
     }
 }
@@ -35,6 +48,14 @@ impl Debug for Message {
             Self::Interrupt => write!(f, "Interrupt"),
             Self::Keypress(keycode) => f.debug_tuple("Keypress").field(keycode).finish(),
             Self::Error(err) => f.debug_tuple("Error").field(err).finish(),
+            Self::Mouse(event) => f.debug_tuple("Mouse").field(event).finish(),
+            Self::Paste(text) => f.debug_tuple("Paste").field(text).finish(),
+            Self::Focus(focused) => f.debug_tuple("Focus").field(focused).finish(),
+            Self::Resize { width, height } => f
+                .debug_struct("Resize")
+                .field("width", width)
+                .field("height", height)
+                .finish(),
         }
     }
 }
@@ -47,3 +68,18 @@ where
         Message::Error(Box::new(err))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize() {
+        let message = Message::Resize {
+            width: 80,
+            height: 24,
+        };
+        assert_eq!(message.r#type(), MessageType::Resize);
+        assert_eq!(format!("{:?}", message), "Resize { width: 80, height: 24 }");
+    }
+}
@@ -9,3 +9,9 @@ pub use message::*;
 
 pub mod keycode;
 pub use keycode::*;
+
+pub mod mouse;
+pub use mouse::*;
+
+pub mod plugin;
+pub use plugin::*;
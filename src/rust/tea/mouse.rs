@@ -0,0 +1,172 @@
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    LEFT,
+    MIDDLE,
+    RIGHT,
+    WHEEL_UP,
+    WHEEL_DOWN,
+    WHEEL_LEFT,
+    WHEEL_RIGHT,
+    BACK,
+    FORWARD,
+
+    // motion with no button held
+    NONE,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    PRESS,
+    RELEASE,
+    DRAG,
+    MOVE,
+    WHEEL,
+}
+
+// NOTE: each mode also reports what the ones before it do, all of them are sent in SGR encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseTracking {
+    // presses, releases and the wheel, mode 1000
+    CLICK,
+    // motion while a button is held, mode 1002
+    DRAG,
+    // all motion, mode 1003
+    MOTION,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MouseEvent {
+    pub button: MouseButton,
+    pub action: MouseAction,
+    // 1-based, as in ProgramContext
+    pub row: isize,
+    pub col: isize,
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+const BUTTON_SHIFT: u32 = 0b00000100;
+const BUTTON_ALT: u32 = 0b00001000;
+const BUTTON_CTRL: u32 = 0b00010000;
+const BUTTON_MOTION: u32 = 0b00100000;
+const BUTTON_WHEEL: u32 = 0b01000000;
+const BUTTON_EXTRA: u32 = 0b10000000;
+
+impl MouseTracking {
+    pub(crate) fn mode(&self) -> u32 {
+        match self {
+            Self::CLICK => 1000,
+            Self::DRAG => 1002,
+            Self::MOTION => 1003,
+        }
+    }
+}
+
+impl MouseEvent {
+    // NOTE: ESC [ < code ; col ; row M, the low two bits of code pick the button, m marks a release
+    pub(crate) fn from_sgr(code: u32, col: u32, row: u32, release: bool) -> Self {
+        let low = code & 0b11;
+        let (button, action) = if code & BUTTON_WHEEL != 0 {
+            let button = match low {
+                0 => MouseButton::WHEEL_UP,
+                1 => MouseButton::WHEEL_DOWN,
+                2 => MouseButton::WHEEL_LEFT,
+                _ => MouseButton::WHEEL_RIGHT,
+            };
+            (button, MouseAction::WHEEL)
+        } else {
+            let button = match (code & BUTTON_EXTRA != 0, low) {
+                (false, 0) => MouseButton::LEFT,
+                (false, 1) => MouseButton::MIDDLE,
+                (false, 2) => MouseButton::RIGHT,
+                (true, 0) => MouseButton::BACK,
+                (true, 1) => MouseButton::FORWARD,
+                _ => MouseButton::NONE,
+            };
+            let action = match (code & BUTTON_MOTION != 0, button) {
+                (true, MouseButton::NONE) => MouseAction::MOVE,
+                (true, _) => MouseAction::DRAG,
+                (false, _) if release => MouseAction::RELEASE,
+                (false, _) => MouseAction::PRESS,
+            };
+            (button, action)
+        };
+
+        Self {
+            button,
+            action,
+            row: row as isize,
+            col: col as isize,
+            shift: code & BUTTON_SHIFT != 0,
+            alt: code & BUTTON_ALT != 0,
+            ctrl: code & BUTTON_CTRL != 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(code: u32, release: bool) -> (MouseButton, MouseAction) {
+        let event = MouseEvent::from_sgr(code, 10, 5, release);
+        (event.button, event.action)
+    }
+
+    #[test]
+    fn buttons() {
+        assert_eq!(event(0, false), (MouseButton::LEFT, MouseAction::PRESS));
+        assert_eq!(event(1, false), (MouseButton::MIDDLE, MouseAction::PRESS));
+        assert_eq!(event(2, false), (MouseButton::RIGHT, MouseAction::PRESS));
+        assert_eq!(event(0, true), (MouseButton::LEFT, MouseAction::RELEASE));
+        assert_eq!(event(128, false), (MouseButton::BACK, MouseAction::PRESS));
+        assert_eq!(
+            event(129, true),
+            (MouseButton::FORWARD, MouseAction::RELEASE)
+        );
+    }
+
+    #[test]
+    fn motion() {
+        assert_eq!(event(32, false), (MouseButton::LEFT, MouseAction::DRAG));
+        assert_eq!(event(34, false), (MouseButton::RIGHT, MouseAction::DRAG));
+        assert_eq!(event(35, false), (MouseButton::NONE, MouseAction::MOVE));
+    }
+
+    #[test]
+    fn wheel() {
+        assert_eq!(
+            event(64, false),
+            (MouseButton::WHEEL_UP, MouseAction::WHEEL)
+        );
+        assert_eq!(
+            event(65, false),
+            (MouseButton::WHEEL_DOWN, MouseAction::WHEEL)
+        );
+        assert_eq!(
+            event(66, false),
+            (MouseButton::WHEEL_LEFT, MouseAction::WHEEL)
+        );
+        assert_eq!(
+            event(67, false),
+            (MouseButton::WHEEL_RIGHT, MouseAction::WHEEL)
+        );
+    }
+
+    #[test]
+    fn modifiers_and_position() {
+        let event = MouseEvent::from_sgr(4 | 8 | 16, 10, 5, false);
+        assert_eq!(
+            (event.button, event.action),
+            (MouseButton::LEFT, MouseAction::PRESS)
+        );
+        assert!(event.shift && event.alt && event.ctrl);
+        assert_eq!((event.row, event.col), (5, 10));
+
+        let event = MouseEvent::from_sgr(8 | 65, 1, 1, false);
+        assert_eq!(event.button, MouseButton::WHEEL_DOWN);
+        assert!(!event.shift && event.alt && !event.ctrl);
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    error::Error,
+    ffi::{CStr, c_char},
+    fmt::Display,
+    fs::read_dir,
+    path::Path,
+    slice::from_raw_parts,
+    str::from_utf8,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    tea::{Command, KeyCode, KeyName, Message, Model, ProgramContext, ProgramInner},
+    uv::{Library, LibraryError},
+};
+
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+pub const PLUGIN_ENTRY_SYMBOL: &'static str = "tea_plugin_entry";
+
+pub const PLUGIN_MESSAGE_TERMINATE: u32 = 0;
+pub const PLUGIN_MESSAGE_INTERRUPT: u32 = 1;
+pub const PLUGIN_MESSAGE_KEYPRESS: u32 = 2;
+pub const PLUGIN_MESSAGE_ERROR: u32 = 3;
+
+// NOTE: everything crossing the plugin boundary is repr(C), the shared object owns every pointer
+#[repr(C)]
+pub struct PluginContext {
+    pub width: i32,
+    pub height: i32,
+    pub cursor_row: isize,
+    pub cursor_col: isize,
+}
+
+#[repr(C)]
+pub struct PluginMessage {
+    pub kind: u32,
+    pub data: *const u8,
+    pub len: usize,
+}
+
+#[repr(C)]
+pub struct PluginCommandEntry {
+    pub name: *const c_char,
+    pub call: unsafe extern "C" fn(context: *const PluginContext) -> PluginMessage,
+}
+
+#[repr(C)]
+pub struct PluginComponentEntry {
+    pub name: *const c_char,
+    pub view: unsafe extern "C" fn(len: *mut usize) -> *const u8,
+}
+
+#[repr(C)]
+pub struct PluginEntry {
+    pub abi_version: u32,
+    pub name: *const c_char,
+    pub commands: *const PluginCommandEntry,
+    pub commands_len: usize,
+    pub components: *const PluginComponentEntry,
+    pub components_len: usize,
+}
+
+pub type PluginEntryFn = unsafe extern "C" fn() -> *const PluginEntry;
+
+#[derive(Debug)]
+pub enum PluginError {
+    LoadError(LibraryError),
+    AbiMismatch { name: String, abi_version: u32 },
+    InvalidEntry(String),
+    CommandError { name: String, message: String },
+}
+
+pub struct Plugin {
+    name: String,
+    entry: *const PluginEntry,
+    library: Library,
+}
+
+// SAFETY: entry points into the shared object's static tables, which are never written after
+// tea_plugin_entry returns and stay mapped as long as library is open. The library itself is only
+// used by load, before the plugin can be shared, and by close, which takes the plugin by value
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+pub struct PluginCommand {
+    plugin: Arc<Plugin>,
+    call: unsafe extern "C" fn(context: *const PluginContext) -> PluginMessage,
+}
+
+pub struct PluginComponent {
+    plugin: Arc<Plugin>,
+    view: unsafe extern "C" fn(len: *mut usize) -> *const u8,
+}
+
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Arc<Plugin>>,
+}
+
+unsafe fn to_string(raw: *const c_char) -> Option<String> {
+    if raw.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(raw).to_string_lossy().into_owned())
+    }
+}
+
+unsafe fn to_slice<'a, T>(raw: *const T, len: usize) -> &'a [T] {
+    if raw.is_null() || len == 0 {
+        &[]
+    } else {
+        from_raw_parts(raw, len)
+    }
+}
+
+// a keypress from a plugin carries a char only when its data is exactly one
+fn single_char(data: &[u8]) -> Option<char> {
+    let mut chars = from_utf8(data).ok()?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+impl Plugin {
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        let library = Library::open(path)?;
+        let entry = unsafe { (*library.get::<PluginEntryFn>(PLUGIN_ENTRY_SYMBOL)?)() };
+        if entry.is_null() {
+            return Err(PluginError::InvalidEntry(path.display().to_string()));
+        }
+
+        let name = unsafe { to_string((*entry).name) }
+            .ok_or_else(|| PluginError::InvalidEntry(path.display().to_string()))?;
+        let abi_version = unsafe { (*entry).abi_version };
+        if abi_version != PLUGIN_ABI_VERSION {
+            return Err(PluginError::AbiMismatch { name, abi_version });
+        }
+
+        Ok(Self {
+            name,
+            entry,
+            library,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn commands(&self) -> Vec<String> {
+        unsafe { to_slice((*self.entry).commands, (*self.entry).commands_len) }
+            .iter()
+            .filter_map(|command| unsafe { to_string(command.name) })
+            .collect()
+    }
+
+    pub fn components(&self) -> Vec<String> {
+        unsafe { to_slice((*self.entry).components, (*self.entry).components_len) }
+            .iter()
+            .filter_map(|component| unsafe { to_string(component.name) })
+            .collect()
+    }
+
+    pub fn close(self) {
+        self.library.close();
+    }
+}
+
+impl PluginRegistry {
+    pub fn load(&mut self, path: &Path) -> Result<&Plugin, PluginError> {
+        let plugin = Arc::new(Plugin::load(path)?);
+        self.plugins.push(plugin);
+        Ok(self.plugins.last().unwrap())
+    }
+
+    pub fn load_dir(&mut self, dir: &Path) -> Result<Vec<PluginError>, PluginError> {
+        let mut errors = Vec::new();
+        let entries = read_dir(dir)
+            .map_err(|err| PluginError::InvalidEntry(format!("{}: {}", dir.display(), err)))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "so") {
+                if let Err(err) = self.load(&path) {
+                    errors.push(err);
+                }
+            }
+        }
+        Ok(errors)
+    }
+
+    pub fn plugins(&self) -> impl Iterator<Item = &Plugin> {
+        self.plugins.iter().map(|plugin| plugin.as_ref())
+    }
+
+    pub fn command(&self, name: &str) -> Option<PluginCommand> {
+        self.plugins.iter().find_map(|plugin| {
+            unsafe { to_slice((*plugin.entry).commands, (*plugin.entry).commands_len) }
+                .iter()
+                .find(|command| unsafe { to_string(command.name) }.as_deref() == Some(name))
+                .map(|command| PluginCommand {
+                    plugin: plugin.clone(),
+                    call: command.call,
+                })
+        })
+    }
+
+    pub fn component(&self, name: &str) -> Option<PluginComponent> {
+        self.plugins.iter().find_map(|plugin| {
+            unsafe { to_slice((*plugin.entry).components, (*plugin.entry).components_len) }
+                .iter()
+                .find(|component| unsafe { to_string(component.name) }.as_deref() == Some(name))
+                .map(|component| PluginComponent {
+                    plugin: plugin.clone(),
+                    view: component.view,
+                })
+        })
+    }
+}
+
+impl PluginCommand {
+    pub fn plugin(&self) -> &str {
+        self.plugin.name()
+    }
+}
+
+impl PluginComponent {
+    pub fn plugin(&self) -> &str {
+        self.plugin.name()
+    }
+}
+
+impl<M: Model> Command<M> for PluginCommand {
+    fn call(&mut self, context: &Mutex<ProgramContext>, _: &Mutex<ProgramInner>) -> Message {
+        let context = match context.lock() {
+            Ok(context) => PluginContext {
+                width: context.width,
+                height: context.height,
+                cursor_row: context.cursor.0,
+                cursor_col: context.cursor.1,
+            },
+            Err(err) => panic!("{}", err),
+        };
+
+        let message = unsafe { (self.call)(&context) };
+        let data = unsafe { to_slice(message.data, message.len) }.to_vec();
+        match message.kind {
+            PLUGIN_MESSAGE_TERMINATE => Message::Terminate,
+            PLUGIN_MESSAGE_INTERRUPT => Message::Interrupt,
+            PLUGIN_MESSAGE_KEYPRESS => Message::Keypress(KeyCode {
+                char: single_char(&data),
+                ..KeyCode::new(KeyName::NONE, data)
+            }),
+            PLUGIN_MESSAGE_ERROR => Message::from(PluginError::CommandError {
+                name: self.plugin.name().to_string(),
+                message: String::from_utf8_lossy(&data).into_owned(),
+            }),
+            kind => Message::from(PluginError::CommandError {
+                name: self.plugin.name().to_string(),
+                message: format!("unexpected message kind [{}]", kind),
+            }),
+        }
+    }
+}
+
+impl<M: Model> From<PluginCommand> for Box<dyn Command<M>> {
+    fn from(value: PluginCommand) -> Self {
+        Box::new(value)
+    }
+}
+
+impl Model for PluginComponent {
+    fn view(&self) -> Box<[u8]> {
+        let mut len = 0;
+        let view = unsafe { (self.view)(&mut len) };
+        unsafe { to_slice(view, len) }.into()
+    }
+}
+
+impl Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LoadError(err) => write!(f, "Plugin::load: {}", err),
+            Self::AbiMismatch { name, abi_version } => write!(
+                f,
+                "Plugin::load: [{}] was built for abi [{}] but expected [{}]",
+                name, abi_version, PLUGIN_ABI_VERSION
+            ),
+            Self::InvalidEntry(msg) => write!(f, "Plugin::load: invalid entry: {}", msg),
+            Self::CommandError { name, message } => write!(f, "Plugin[{}]: {}", name, message),
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+impl From<LibraryError> for PluginError {
+    fn from(value: LibraryError) -> Self {
+        Self::LoadError(value)
+    }
+}
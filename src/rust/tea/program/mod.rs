@@ -7,20 +7,28 @@ pub use command::*;
 pub mod error;
 pub use error::*;
 
+pub mod options;
+pub use options::*;
+
 use std::{
     cell::RefCell,
-    io::{stdin, stdout},
+    io::{Write, stdin, stdout},
+    mem::take,
     os::fd::AsRawFd,
+    panic::{PanicHookInfo, set_hook, take_hook},
     rc::Rc,
     str::from_utf8,
-    sync::{Mutex, mpsc::channel},
+    sync::{Arc, Mutex, PoisonError, mpsc::channel},
+    thread::panicking,
+    time::Duration,
 };
 
 use crate::{
     tea::{KeyCodeParser, Message, MessageType, Model},
     uv::{
-        Buf, CheckHandle, ConvertBuf, Handle, HandleType, IHandle, IStreamHandle, Loop, Mode,
-        RunMode, StreamHandle, TTYStream, WriteRequest, guess_handle,
+        Buf, CheckHandle, ConvertBuf, ErrnoContext, Handle, HandleType, IHandle, IStreamHandle,
+        Loop, Mode, RunMode, SIGWINCH, SignalHandle, StreamHandle, TTYStream, TimerHandle, UvError,
+        WriteRequest, guess_handle, reset_tty_mode,
     },
 };
 
@@ -32,7 +40,11 @@ pub struct Program<'a, M: Model> {
     r#loop: Loop,
     inner: Mutex<ProgramInner>,
     updates: UpdateBroker<'a, M>,
-    keycode_parser: KeyCodeParser,
+    keycode_parser: RefCell<KeyCodeParser>,
+    options: ProgramOptions,
+    terminal: TerminalRestore,
+    // the hook that was installed before run, put back once the terminal is restored
+    panic_hook: Option<Arc<PanicHook>>,
 }
 
 pub struct ProgramContext {
@@ -46,17 +58,50 @@ pub struct ProgramInner {
     r#in: TTYStream,
     r#out: TTYStream,
     messages: CheckHandle,
+    escape: TimerHandle,
+    resize: SignalHandle,
+    // set when the next render has to clear what was drawn before
+    redraw: bool,
 }
 
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static>;
+
+// NOTE: shared with the panic hook, whichever runs first puts the terminal back
+#[derive(Clone, Default)]
+struct TerminalRestore(Arc<Mutex<Option<String>>>);
+
 impl ProgramInner {
     pub fn terminate(&mut self) {
         self.r#in.read_stop();
         self.messages.stop();
+        let _ = self.escape.stop();
+        let _ = self.resize.stop();
+    }
+}
+
+impl TerminalRestore {
+    fn arm(&self, sequence: String) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(sequence);
+    }
+
+    // written directly instead of through out, the loop may not be running anymore
+    fn restore(&self) {
+        let sequence = self.0.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if let Some(sequence) = sequence {
+            let mut stdout = stdout();
+            let _ = stdout.write_all(sequence.as_bytes());
+            let _ = stdout.flush();
+            let _ = reset_tty_mode();
+        }
     }
 }
 
 impl<'a, M: Model> Program<'a, M> {
     pub fn init(model: M) -> Result<Self, ProgramError> {
+        Self::init_with_loop(model, Loop::default())
+    }
+
+    pub fn init_with_loop(model: M, mut r#loop: Loop) -> Result<Self, ProgramError> {
         struct InitDropGaurd {
             r#in: RefCell<TTYStream>,
         }
@@ -66,15 +111,13 @@ impl<'a, M: Model> Program<'a, M> {
             }
         }
 
-        let mut r#loop = Loop::default();
-
         let stdin = stdin().as_raw_fd();
         let stdin_guess = guess_handle(stdin);
         if stdin_guess != HandleType::TTY {
             panic!("expected stdin to be TTY but found [{}]", stdin_guess);
         }
 
-        let r#in: Rc<TTYStream> = Rc::new(r#loop.new_tty(stdin)?);
+        let r#in: Rc<TTYStream> = Rc::new(r#loop.new_tty(stdin).context_for("tty_init", "stdin")?);
         let guard = InitDropGaurd {
             r#in: RefCell::new(*r#in.clone()),
         };
@@ -85,30 +128,39 @@ impl<'a, M: Model> Program<'a, M> {
             panic!("expected stdout to be TTY but found [{}]", stdout_guess);
         }
 
-        let mut out = r#loop.new_tty(stdout)?;
-        let (width, height) = out.get_winsize()?;
+        let mut out = r#loop.new_tty(stdout).context_for("tty_init", "stdout")?;
+        let (width, height) = out.get_winsize().context("tty_get_winsize")?;
 
-        guard.r#in.borrow_mut().set_mode(Mode::RAW)?;
+        guard
+            .r#in
+            .borrow_mut()
+            .set_mode(Mode::RAW)
+            .context("tty_set_mode")?;
 
         let mut report = Buf::new();
-        guard.r#in.borrow_mut().read_start(
-            |_: &Handle, suggested_size| Some(Buf::new_with_len(suggested_size)),
-            |_: &StreamHandle, nread, buf: Buf| {
-                match nread {
-                    Ok(len) => {
-                        report.append(&buf.as_ref()[..len as usize].to_buf());
-                        if report[report.len() - 2] == b'R' {
-                            guard.r#in.borrow_mut().read_stop();
+        guard
+            .r#in
+            .borrow_mut()
+            .read_start(
+                |_: &Handle, suggested_size| Some(Buf::new_with_len(suggested_size)),
+                |_: &StreamHandle, nread, buf: Buf| {
+                    match nread {
+                        Ok(len) => {
+                            report.append(&buf.as_ref()[..len as usize].to_buf());
+                            if report[report.len() - 2] == b'R' {
+                                guard.r#in.borrow_mut().read_stop();
+                            }
                         }
-                    }
-                    Err(err) => panic!("{}", err),
-                };
-            },
-        )?;
+                        Err(err) => panic!("{}", err),
+                    };
+                },
+            )
+            .context("read_start")?;
 
-        out.write(WriteRequest::new(), &[Buf::from(CPR_REQUEST)], ())?;
+        out.write(WriteRequest::new(), &[Buf::from(CPR_REQUEST)], ())
+            .context("write")?;
 
-        r#loop.run(RunMode::DEFAULT)?;
+        r#loop.run(RunMode::DEFAULT).context("run")?;
 
         let mut keycode_parser = KeyCodeParser::default();
         keycode_parser.buffer(&report);
@@ -121,13 +173,15 @@ impl<'a, M: Model> Program<'a, M> {
 
                 Ok((row, col))
             }
-            None => Err(ProgramError::InitError(format!(
+            _ => Err(ProgramError::InitError(format!(
                 "failed to parse cursor position report: {:?}",
                 report.as_bytes()
             ))),
         }?;
 
-        let messages = r#loop.new_check()?;
+        let messages = r#loop.new_check().context("check_init")?;
+        let escape = r#loop.new_timer().context("timer_init")?;
+        let resize = r#loop.new_signal().context("signal_init")?;
         Ok(Self {
             model,
             context: Mutex::new(ProgramContext {
@@ -141,37 +195,119 @@ impl<'a, M: Model> Program<'a, M> {
                 r#in: *r#in,
                 out,
                 messages,
+                escape,
+                resize,
+                redraw: false,
             }),
             updates: Default::default(),
-            keycode_parser,
+            keycode_parser: RefCell::new(keycode_parser),
+            options: ProgramOptions::default(),
+            terminal: TerminalRestore::default(),
+            panic_hook: None,
         })
     }
 
+    pub fn with_options(mut self, options: ProgramOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &ProgramOptions {
+        &self.options
+    }
+
+    // NOTE: the terminal is restored however run ends, including a panic while it runs
     pub fn run(&mut self) -> Result<(), ProgramError> {
-        self.inner.lock().unwrap().r#in.set_mode(Mode::RAW)?;
+        self.install_panic_hook();
+        let result = self.run_loop();
+        self.restore();
+        result
+    }
+
+    fn run_loop(&mut self) -> Result<(), ProgramError> {
+        match self.inner.lock() {
+            Ok(mut inner) => {
+                inner.r#in.set_mode(Mode::RAW).context("tty_set_mode")?;
+                self.terminal.arm(self.options.disable_sequence());
+
+                let enable = self.options.enable_sequence();
+                if !enable.is_empty() {
+                    inner
+                        .out
+                        .write(WriteRequest::new(), &[Buf::from(enable)], ())
+                        .context("write")?;
+                }
+            }
+            Err(err) => panic!("{}", err),
+        }
+
+        if self.options.alt_screen {
+            match self.context.lock() {
+                Ok(mut context) => {
+                    context.home = (1, 1);
+                    context.cursor = (1, 1);
+                }
+                Err(err) => panic!("{}", err),
+            }
+        }
 
         let (txmessage, rxmessage) = channel::<Message>();
 
         let txmessage_keypress = txmessage.clone();
         match self.inner.lock() {
             Ok(mut inner) => {
-                inner.r#in.read_start(
-                    |_: &Handle, suggested_size| Some(Buf::new_with_len(suggested_size)),
-                    |_: &StreamHandle, nread, buf: Buf| {
-                        match nread {
-                            Ok(len) => {
-                                self.keycode_parser
-                                    .buffer(&buf.as_ref()[..len as usize].to_buf());
-                                while let Some(keycode) = self.keycode_parser.parse_keycode() {
-                                    txmessage_keypress.send(Message::Keypress(keycode)).unwrap();
+                inner
+                    .r#in
+                    .read_start(
+                        |_: &Handle, suggested_size| Some(Buf::new_with_len(suggested_size)),
+                        |_: &StreamHandle, nread, buf: Buf| {
+                            match nread {
+                                Ok(len) => {
+                                    let mut keycode_parser = self.keycode_parser.borrow_mut();
+                                    keycode_parser.buffer(&buf.as_ref()[..len as usize].to_buf());
+                                    while let Some(message) = keycode_parser.parse_message() {
+                                        txmessage_keypress.send(message).unwrap();
+                                    }
+
+                                    // NOTE: a lone ESC is Escape only once nothing follows it in time
+                                    let mut escape = self.inner.lock().unwrap().escape;
+                                    let timeout = if keycode_parser.is_pasting() {
+                                        self.options.paste_timeout
+                                    } else {
+                                        self.options.escape_timeout
+                                    };
+                                    let result = if keycode_parser.is_pending() {
+                                        let keycode_parser = &self.keycode_parser;
+                                        let txmessage_escape = txmessage_keypress.clone();
+                                        escape.start(
+                                            move |_: &TimerHandle| {
+                                                while let Some(message) =
+                                                    keycode_parser.borrow_mut().flush()
+                                                {
+                                                    txmessage_escape.send(message).unwrap();
+                                                }
+                                            },
+                                            timeout,
+                                            Duration::ZERO,
+                                        )
+                                    } else {
+                                        escape.stop()
+                                    };
+                                    if let Err(err) = result {
+                                        txmessage_keypress
+                                            .send(Message::from(UvError::new("timer_start", err)))
+                                            .unwrap();
+                                    }
                                 }
-                            }
-                            Err(err) => {
-                                txmessage_keypress.send(Message::from(err)).unwrap();
-                            }
-                        };
-                    },
-                )?;
+                                Err(err) => {
+                                    txmessage_keypress
+                                        .send(Message::from(UvError::new("read", err)))
+                                        .unwrap();
+                                }
+                            };
+                        },
+                    )
+                    .context("read_start")?;
             }
             Err(err) => panic!("{}", err),
         }
@@ -179,43 +315,127 @@ impl<'a, M: Model> Program<'a, M> {
         let txmessage_command = txmessage.clone();
         match self.inner.lock() {
             Ok(mut inner) => {
-                inner.messages.start(|handle: &CheckHandle| {
-                    for message in rxmessage.try_iter() {
-                        self.updates.publish(
-                            &mut self.model,
-                            &self.context,
-                            &self.inner,
-                            handle.get_loop(),
-                            &txmessage_command,
-                            message,
-                        );
-                    }
-
-                    match (self.context.lock(), self.inner.lock()) {
-                        (Ok(context), Ok(mut inner)) => {
-                            if let Err(err) = inner.out.write(
-                                WriteRequest::new(),
-                                &[
-                                    Buf::from(format!(
-                                        "\x1B[{};{}H",
-                                        context.home.0, context.home.1,
-                                    )),
-                                    Buf::from(self.model.view()),
-                                ],
-                                (),
-                            ) {
-                                txmessage_command.send(Message::from(err)).unwrap();
+                inner
+                    .messages
+                    .start(|handle: &CheckHandle| {
+                        for message in rxmessage.try_iter() {
+                            self.updates.publish(
+                                &mut self.model,
+                                &self.context,
+                                &self.inner,
+                                handle.get_loop(),
+                                &txmessage_command,
+                                message,
+                            );
+                        }
+
+                        match (self.context.lock(), self.inner.lock()) {
+                            (Ok(context), Ok(mut inner)) => {
+                                // a full redraw clears everything below home before drawing
+                                let clear = if take(&mut inner.redraw) {
+                                    "\x1B[J"
+                                } else {
+                                    ""
+                                };
+                                if let Err(err) = inner.out.write(
+                                    WriteRequest::new(),
+                                    &[
+                                        Buf::from(format!(
+                                            "\x1B[{};{}H{}",
+                                            context.home.0, context.home.1, clear,
+                                        )),
+                                        Buf::from(self.model.view()),
+                                    ],
+                                    (),
+                                ) {
+                                    txmessage_command
+                                        .send(Message::from(UvError::new("write", err)))
+                                        .unwrap();
+                                }
                             }
+                            (Err(err), _) => panic!("{}", err),
+                            (_, Err(err)) => panic!("{}", err),
                         }
-                        (Err(err), _) => panic!("{}", err),
-                        (_, Err(err)) => panic!("{}", err),
-                    }
-                })?;
+                    })
+                    .context("check_start")?;
+            }
+            Err(err) => panic!("{}", err),
+        }
+
+        let txmessage_resize = txmessage.clone();
+        match self.inner.lock() {
+            Ok(mut inner) => {
+                inner
+                    .resize
+                    .start(
+                        |_: &SignalHandle, _| {
+                            let (width, height) = match self.inner.lock() {
+                                Ok(mut inner) => match inner.out.get_winsize() {
+                                    Ok(size) => {
+                                        inner.redraw = true;
+                                        size
+                                    }
+                                    Err(err) => {
+                                        txmessage_resize
+                                            .send(Message::from(UvError::new(
+                                                "tty_get_winsize",
+                                                err,
+                                            )))
+                                            .unwrap();
+                                        return;
+                                    }
+                                },
+                                Err(err) => panic!("{}", err),
+                            };
+
+                            match self.context.lock() {
+                                Ok(mut context) => {
+                                    context.width = width;
+                                    context.height = height;
+                                }
+                                Err(err) => panic!("{}", err),
+                            }
+                            txmessage_resize
+                                .send(Message::Resize { width, height })
+                                .unwrap();
+                        },
+                        SIGWINCH as i32,
+                    )
+                    .context("signal_start")?;
             }
             Err(err) => panic!("{}", err),
         }
 
-        Ok(self.r#loop.run(RunMode::DEFAULT)?)
+        Ok(self.r#loop.run(RunMode::DEFAULT).context("run")?)
+    }
+
+    fn install_panic_hook(&mut self) {
+        if self.panic_hook.is_some() {
+            return;
+        }
+
+        let previous: Arc<PanicHook> = Arc::new(take_hook());
+        let hook_previous = previous.clone();
+        let terminal = self.terminal.clone();
+        set_hook(Box::new(move |info| {
+            // the panic message goes to a terminal that is usable again
+            terminal.restore();
+            hook_previous(info);
+        }));
+        self.panic_hook = Some(previous);
+    }
+
+    fn restore(&mut self) {
+        self.terminal.restore();
+
+        // hooks cannot be swapped while unwinding, the installed one has nothing left to restore
+        if panicking() {
+            return;
+        }
+        if let Some(previous) = self.panic_hook.take() {
+            let _ = take_hook();
+            set_hook(Box::new(move |info| previous(info)));
+        }
     }
 
     pub fn update<UH>(&mut self, r#type: MessageType, handler: UH)
@@ -228,6 +448,6 @@ impl<'a, M: Model> Program<'a, M> {
 
 impl<'a, M: Model> Drop for Program<'a, M> {
     fn drop(&mut self) {
-        self.inner.lock().unwrap().r#in.reset_mode().unwrap();
+        self.restore();
     }
 }
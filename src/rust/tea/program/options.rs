@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use crate::tea::MouseTracking;
+
+pub const ESCAPE_TIMEOUT: Duration = Duration::from_millis(25);
+pub const PASTE_TIMEOUT: Duration = Duration::from_secs(1);
+
+// kitty progressive enhancement flags, pushed with ESC [ > flags u
+pub const KITTY_DISAMBIGUATE: u32 = 0b00001;
+pub const KITTY_REPORT_EVENTS: u32 = 0b00010;
+pub const KITTY_REPORT_ALTERNATES: u32 = 0b00100;
+pub const KITTY_REPORT_ALL_KEYS: u32 = 0b01000;
+pub const KITTY_REPORT_TEXT: u32 = 0b10000;
+
+#[derive(Debug, Clone)]
+pub struct ProgramOptions {
+    // how long a lone ESC waits for the rest of a sequence before it is the Escape key
+    pub(crate) escape_timeout: Duration,
+    pub(crate) kitty_keyboard: u32,
+    pub(crate) modify_other_keys: bool,
+    pub(crate) mouse: Option<MouseTracking>,
+    // pasted text arrives as one Message::Paste instead of a keypress per character
+    pub(crate) bracketed_paste: bool,
+    // how long a paste may go quiet before it is handed over without its closing marker
+    pub(crate) paste_timeout: Duration,
+    pub(crate) focus_events: bool,
+    pub(crate) alt_screen: bool,
+    pub(crate) hide_cursor: bool,
+}
+
+impl ProgramOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn escape_timeout(mut self, timeout: Duration) -> Self {
+        self.escape_timeout = timeout;
+        self
+    }
+
+    // NOTE: terminals without the protocol ignore the request and keep the legacy encoding
+    pub fn kitty_keyboard(mut self, flags: u32) -> Self {
+        self.kitty_keyboard = flags;
+        self
+    }
+
+    pub fn modify_other_keys(mut self, enable: bool) -> Self {
+        self.modify_other_keys = enable;
+        self
+    }
+
+    pub fn mouse(mut self, tracking: MouseTracking) -> Self {
+        self.mouse = Some(tracking);
+        self
+    }
+
+    pub fn bracketed_paste(mut self, enable: bool) -> Self {
+        self.bracketed_paste = enable;
+        self
+    }
+
+    pub fn paste_timeout(mut self, timeout: Duration) -> Self {
+        self.paste_timeout = timeout;
+        self
+    }
+
+    pub fn focus_events(mut self, enable: bool) -> Self {
+        self.focus_events = enable;
+        self
+    }
+
+    // NOTE: the alternate screen is cleared on entry and the view is drawn from its top left
+    pub fn alt_screen(mut self, enable: bool) -> Self {
+        self.alt_screen = enable;
+        self
+    }
+
+    pub fn hide_cursor(mut self, enable: bool) -> Self {
+        self.hide_cursor = enable;
+        self
+    }
+
+    pub(crate) fn enable_sequence(&self) -> String {
+        let mut sequence = String::new();
+        if self.alt_screen {
+            sequence.push_str("\x1b[?1049h\x1b[2J\x1b[H");
+        }
+        if self.hide_cursor {
+            sequence.push_str("\x1b[?25l");
+        }
+        if self.bracketed_paste {
+            sequence.push_str("\x1b[?2004h");
+        }
+        if self.modify_other_keys {
+            sequence.push_str("\x1b[>4;2m");
+        }
+        if self.kitty_keyboard != 0 {
+            sequence.push_str(&format!("\x1b[>{}u", self.kitty_keyboard));
+        }
+        if let Some(tracking) = self.mouse {
+            sequence.push_str(&format!("\x1b[?{}h\x1b[?1006h", tracking.mode()));
+        }
+        if self.focus_events {
+            sequence.push_str("\x1b[?1004h");
+        }
+        sequence
+    }
+
+    // undoes enable_sequence in reverse order
+    pub(crate) fn disable_sequence(&self) -> String {
+        let mut sequence = String::new();
+        if self.focus_events {
+            sequence.push_str("\x1b[?1004l");
+        }
+        if let Some(tracking) = self.mouse {
+            sequence.push_str(&format!("\x1b[?1006l\x1b[?{}l", tracking.mode()));
+        }
+        if self.kitty_keyboard != 0 {
+            sequence.push_str("\x1b[<u");
+        }
+        if self.modify_other_keys {
+            sequence.push_str("\x1b[>4m");
+        }
+        if self.bracketed_paste {
+            sequence.push_str("\x1b[?2004l");
+        }
+        if self.hide_cursor {
+            sequence.push_str("\x1b[?25h");
+        }
+        if self.alt_screen {
+            sequence.push_str("\x1b[?1049l");
+        }
+        sequence
+    }
+}
+
+impl Default for ProgramOptions {
+    fn default() -> Self {
+        Self {
+            escape_timeout: ESCAPE_TIMEOUT,
+            kitty_keyboard: 0,
+            modify_other_keys: false,
+            mouse: None,
+            bracketed_paste: true,
+            paste_timeout: PASTE_TIMEOUT,
+            focus_events: false,
+            alt_screen: false,
+            hide_cursor: false,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::{any::TypeId, os::raw::c_void};
+
+// type
+
+// NOTE: owns a boxed value of the type it was created with and drops it with that type
+pub struct UserData {
+    raw: *mut c_void,
+    type_id: TypeId,
+    drop: unsafe fn(*mut c_void),
+}
+
+// fn
+
+unsafe fn drop_data<D>(raw: *mut c_void) {
+    drop(Box::from_raw(raw as *mut D));
+}
+
+// impl
+
+impl UserData {
+    pub fn new<D: 'static>(data: D) -> Self {
+        Self {
+            raw: Box::into_raw(Box::new(data)) as *mut c_void,
+            type_id: TypeId::of::<D>(),
+            drop: drop_data::<D>,
+        }
+    }
+
+    pub fn is<D: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<D>()
+    }
+
+    pub fn get<D: 'static>(&self) -> Option<&D> {
+        if self.is::<D>() {
+            Some(unsafe { &*(self.raw as *const D) })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut<D: 'static>(&mut self) -> Option<&mut D> {
+        if self.is::<D>() {
+            Some(unsafe { &mut *(self.raw as *mut D) })
+        } else {
+            None
+        }
+    }
+}
+
+// trait
+
+impl Drop for UserData {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(self.raw) };
+    }
+}
@@ -0,0 +1,75 @@
+use std::{io, os::raw::c_short, time::Duration};
+
+use crate::uv::{Errno, Loop, POLLIN, nfds_t, poll, pollfd};
+
+// type
+
+pub struct PollDriver {
+    r#loop: Loop,
+    fds: Vec<pollfd>,
+}
+
+// impl
+
+impl PollDriver {
+    pub fn new(r#loop: Loop) -> Self {
+        Self {
+            r#loop,
+            fds: Vec::new(),
+        }
+    }
+
+    pub fn backend(&self) -> pollfd {
+        pollfd {
+            fd: self.r#loop.backend_fd(),
+            events: POLLIN as c_short,
+            revents: 0,
+        }
+    }
+
+    pub fn timeout(&self, host_timeout: Option<Duration>) -> i32 {
+        let timeout = match (self.r#loop.backend_timeout(), host_timeout) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (timeout, None) | (None, timeout) => timeout,
+        };
+        match timeout {
+            Some(timeout) => timeout.as_millis().min(i32::MAX as u128) as i32,
+            None => -1,
+        }
+    }
+
+    pub fn poll_once(
+        &mut self,
+        host_fds: &mut [pollfd],
+        host_timeout: Option<Duration>,
+    ) -> Result<bool, Errno> {
+        let timeout = self.timeout(host_timeout);
+        self.fds.clear();
+        self.fds.extend_from_slice(host_fds);
+        self.fds.push(self.backend());
+
+        let result = unsafe { poll(self.fds.as_mut_ptr(), self.fds.len() as nfds_t, timeout) };
+        if result < 0 {
+            return match Errno::from(io::Error::last_os_error()) {
+                Errno::EINTR => Ok(self.r#loop.alive()),
+                err => Err(err),
+            };
+        }
+        host_fds.copy_from_slice(&self.fds[..host_fds.len()]);
+
+        // timers may be due even when the backend fd stayed quiet
+        self.r#loop.run_nowait_after_ready()
+    }
+
+    pub fn run<F>(&mut self, host_fds: &mut [pollfd], mut on_ready: F) -> Result<(), Errno>
+    where
+        F: FnMut(&mut [pollfd]) -> bool,
+    {
+        loop {
+            let alive = self.poll_once(host_fds, None)?;
+            if !on_ready(host_fds) || (!alive && host_fds.is_empty()) {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -1,8 +1,13 @@
-use std::{error::Error, ffi::CStr, fmt::Display};
+use std::{
+    error::Error,
+    ffi::CStr,
+    fmt::Display,
+    io::{self, ErrorKind},
+};
 
 use crate::{
     inners::{FromInner, IntoInner},
-    uv::{self, uv_err_name, uv_errno_t, uv_strerror},
+    uv::{self, uv_err_name, uv_errno_t, uv_strerror, uv_translate_sys_error},
 };
 
 // type
@@ -97,6 +102,19 @@ pub enum Errno {
     ERRNO_MAX,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UvError {
+    errno: Errno,
+    operation: &'static str,
+    target: Option<String>,
+}
+
+pub trait ErrnoContext<T> {
+    fn context(self, operation: &'static str) -> Result<T, UvError>;
+
+    fn context_for<D: Display>(self, operation: &'static str, target: D) -> Result<T, UvError>;
+}
+
 // impl
 
 impl Errno {
@@ -111,6 +129,80 @@ impl Errno {
             .to_string_lossy()
             .into_owned()
     }
+
+    // NOTE: libuv reserves the codes from -3000 down for its own errors, the rest are -errno
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match self.into_inner() {
+            code if code > -3000 && code < 0 => Some(-code),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Errno::E2BIG => ErrorKind::ArgumentListTooLong,
+            Errno::EACCES | Errno::EPERM => ErrorKind::PermissionDenied,
+            Errno::EADDRINUSE => ErrorKind::AddrInUse,
+            Errno::EADDRNOTAVAIL => ErrorKind::AddrNotAvailable,
+            Errno::EAGAIN => ErrorKind::WouldBlock,
+            Errno::EBUSY | Errno::ETXTBSY => ErrorKind::ResourceBusy,
+            Errno::ECONNABORTED => ErrorKind::ConnectionAborted,
+            Errno::ECONNREFUSED => ErrorKind::ConnectionRefused,
+            Errno::ECONNRESET => ErrorKind::ConnectionReset,
+            Errno::EEXIST => ErrorKind::AlreadyExists,
+            Errno::EFBIG => ErrorKind::FileTooLarge,
+            Errno::EHOSTUNREACH => ErrorKind::HostUnreachable,
+            Errno::EINTR => ErrorKind::Interrupted,
+            Errno::EINVAL | Errno::EAI_BADFLAGS | Errno::EAI_BADHINTS => ErrorKind::InvalidInput,
+            Errno::EISDIR => ErrorKind::IsADirectory,
+            Errno::EMLINK => ErrorKind::TooManyLinks,
+            Errno::ENAMETOOLONG => ErrorKind::InvalidFilename,
+            Errno::ENETDOWN => ErrorKind::NetworkDown,
+            Errno::ENETUNREACH => ErrorKind::NetworkUnreachable,
+            Errno::ENOENT | Errno::EAI_NONAME | Errno::EAI_NODATA => ErrorKind::NotFound,
+            Errno::ENOMEM | Errno::EAI_MEMORY => ErrorKind::OutOfMemory,
+            Errno::ENOSPC => ErrorKind::StorageFull,
+            Errno::ENOSYS | Errno::ENOTSUP => ErrorKind::Unsupported,
+            Errno::ENOTCONN => ErrorKind::NotConnected,
+            Errno::ENOTDIR => ErrorKind::NotADirectory,
+            Errno::ENOTEMPTY => ErrorKind::DirectoryNotEmpty,
+            Errno::EOF => ErrorKind::UnexpectedEof,
+            Errno::EPIPE => ErrorKind::BrokenPipe,
+            Errno::EROFS => ErrorKind::ReadOnlyFilesystem,
+            Errno::ESPIPE => ErrorKind::NotSeekable,
+            Errno::ETIMEDOUT => ErrorKind::TimedOut,
+            Errno::EXDEV => ErrorKind::CrossesDevices,
+            Errno::EILSEQ | Errno::ECHARSET => ErrorKind::InvalidData,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl UvError {
+    pub fn new(operation: &'static str, errno: Errno) -> Self {
+        Self {
+            errno,
+            operation,
+            target: None,
+        }
+    }
+
+    pub fn with_target<D: Display>(mut self, target: D) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    pub fn errno(&self) -> Errno {
+        self.errno
+    }
+
+    pub fn operation(&self) -> &'static str {
+        self.operation
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
 }
 
 // trait
@@ -123,6 +215,88 @@ impl Display for Errno {
 
 impl Error for Errno {}
 
+// NOTE: prints the short name, e.g. fs_open("/etc/x"): ENOENT, the message stays on source()
+impl Display for UvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.target {
+            Some(ref target) => {
+                write!(f, "{}({:?}): {}", self.operation, target, self.errno.name())
+            }
+            None => write!(f, "{}: {}", self.operation, self.errno.name()),
+        }
+    }
+}
+
+impl Error for UvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.errno)
+    }
+}
+
+impl<T> ErrnoContext<T> for Result<T, Errno> {
+    fn context(self, operation: &'static str) -> Result<T, UvError> {
+        self.map_err(|errno| UvError::new(operation, errno))
+    }
+
+    fn context_for<D: Display>(self, operation: &'static str, target: D) -> Result<T, UvError> {
+        self.map_err(|errno| UvError::new(operation, errno).with_target(target))
+    }
+}
+
+impl From<Errno> for io::Error {
+    fn from(value: Errno) -> Self {
+        match value.raw_os_error() {
+            Some(code) => io::Error::from_raw_os_error(code),
+            None => io::Error::new(value.kind(), value),
+        }
+    }
+}
+
+impl From<UvError> for io::Error {
+    fn from(value: UvError) -> Self {
+        io::Error::new(value.errno.kind(), value)
+    }
+}
+
+impl From<io::Error> for Errno {
+    fn from(value: io::Error) -> Self {
+        if let Some(code) = value.raw_os_error() {
+            return Errno::from_inner(unsafe { uv_translate_sys_error(code) });
+        }
+        if let Some(errno) = value.get_ref().and_then(|err| err.downcast_ref::<Errno>()) {
+            return *errno;
+        }
+        if let Some(err) = value
+            .get_ref()
+            .and_then(|err| err.downcast_ref::<UvError>())
+        {
+            return err.errno;
+        }
+
+        match value.kind() {
+            ErrorKind::NotFound => Errno::ENOENT,
+            ErrorKind::PermissionDenied => Errno::EACCES,
+            ErrorKind::ConnectionRefused => Errno::ECONNREFUSED,
+            ErrorKind::ConnectionReset => Errno::ECONNRESET,
+            ErrorKind::ConnectionAborted => Errno::ECONNABORTED,
+            ErrorKind::NotConnected => Errno::ENOTCONN,
+            ErrorKind::AddrInUse => Errno::EADDRINUSE,
+            ErrorKind::AddrNotAvailable => Errno::EADDRNOTAVAIL,
+            ErrorKind::BrokenPipe => Errno::EPIPE,
+            ErrorKind::AlreadyExists => Errno::EEXIST,
+            ErrorKind::WouldBlock => Errno::EAGAIN,
+            ErrorKind::InvalidInput => Errno::EINVAL,
+            ErrorKind::InvalidData => Errno::EILSEQ,
+            ErrorKind::TimedOut => Errno::ETIMEDOUT,
+            ErrorKind::Interrupted => Errno::EINTR,
+            ErrorKind::Unsupported => Errno::ENOTSUP,
+            ErrorKind::UnexpectedEof => Errno::EOF,
+            ErrorKind::OutOfMemory => Errno::ENOMEM,
+            _ => Errno::EIO,
+        }
+    }
+}
+
 // inner
 
 impl FromInner<uv_errno_t> for Errno {
@@ -213,7 +387,7 @@ impl FromInner<uv_errno_t> for Errno {
             uv::uv_errno_t_UV_ENODATA => Errno::ENODATA,
             uv::uv_errno_t_UV_EUNATCH => Errno::EUNATCH,
             uv::uv_errno_t_UV_ERRNO_MAX => Errno::ERRNO_MAX,
-            _ => unreachable!(),
+            _ => Errno::UNKNOWN, // uv_translate_sys_error passes unmapped errnos through
         }
     }
 }
@@ -309,3 +483,82 @@ impl IntoInner<uv_errno_t> for Errno {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [Errno; 8] = [
+        Errno::E2BIG,
+        Errno::EACCES,
+        Errno::EAGAIN,
+        Errno::ENOENT,
+        Errno::EOF,
+        Errno::EAI_NONAME,
+        Errno::ECHARSET,
+        Errno::UNKNOWN,
+    ];
+
+    #[test]
+    fn inner_round_trip() {
+        for errno in SAMPLES {
+            assert_eq!(Errno::from_inner(errno.into_inner()), errno);
+        }
+        assert_eq!(Errno::from_inner(-12345), Errno::UNKNOWN);
+    }
+
+    #[test]
+    fn raw_os_error() {
+        assert_eq!(Errno::ENOENT.raw_os_error(), Some(2));
+        assert_eq!(Errno::EACCES.raw_os_error(), Some(13));
+        // libuv's own codes have no errno behind them
+        assert_eq!(Errno::EOF.raw_os_error(), None);
+        assert_eq!(Errno::EAI_NONAME.raw_os_error(), None);
+        assert_eq!(Errno::ECHARSET.raw_os_error(), None);
+        assert_eq!(Errno::UNKNOWN.raw_os_error(), None);
+    }
+
+    #[test]
+    fn kind() {
+        assert_eq!(Errno::ENOENT.kind(), ErrorKind::NotFound);
+        assert_eq!(Errno::EAI_NONAME.kind(), ErrorKind::NotFound);
+        assert_eq!(Errno::EAGAIN.kind(), ErrorKind::WouldBlock);
+        assert_eq!(Errno::EOF.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(Errno::ECHARSET.kind(), ErrorKind::InvalidData);
+        assert_eq!(Errno::ECANCELED.kind(), ErrorKind::Other);
+        assert_eq!(Errno::UNKNOWN.kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    fn io_error_round_trip() {
+        for errno in SAMPLES {
+            let err = io::Error::from(errno);
+            assert_eq!(err.kind(), errno.kind(), "{:?}", errno);
+            assert_eq!(err.raw_os_error(), errno.raw_os_error(), "{:?}", errno);
+            assert_eq!(Errno::from(err), errno);
+        }
+
+        let err = io::Error::from(UvError::new("read", Errno::EOF));
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(Errno::from(err), Errno::EOF);
+
+        assert_eq!(Errno::from(io::Error::from_raw_os_error(2)), Errno::ENOENT);
+        assert_eq!(
+            Errno::from(io::Error::new(ErrorKind::TimedOut, "slow")),
+            Errno::ETIMEDOUT
+        );
+        assert_eq!(Errno::from(io::Error::other("other")), Errno::EIO);
+    }
+
+    #[test]
+    fn display() {
+        assert!(Errno::ENOENT.to_string().starts_with("ENOENT: "));
+        assert_eq!(UvError::new("read", Errno::EOF).to_string(), "read: EOF");
+
+        let err = UvError::new("fs_open", Errno::ENOENT).with_target("/etc/x");
+        assert_eq!(err.to_string(), "fs_open(\"/etc/x\"): ENOENT");
+        assert_eq!(err.target(), Some("/etc/x"));
+        let source = err.source().and_then(|err| err.downcast_ref::<Errno>());
+        assert_eq!(source, Some(&Errno::ENOENT));
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    task::{Context, Wake, Waker},
+};
+
+use crate::uv::{AsyncHandle, AsyncSender, Errno, IHandle, Loop, RunMode};
+
+// type
+
+type Task<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+struct Tasks<'a> {
+    pending: RefCell<HashMap<usize, Task<'a>>>,
+    next_id: Cell<usize>,
+    polling: Cell<bool>,
+}
+
+struct WakeQueue {
+    ready: Mutex<VecDeque<usize>>,
+    sender: Mutex<Option<AsyncSender>>,
+}
+
+struct TaskWaker {
+    id: usize,
+    queue: Arc<WakeQueue>,
+}
+
+pub struct Executor<'a> {
+    wakeup: AsyncHandle,
+    tasks: Rc<Tasks<'a>>,
+    queue: Arc<WakeQueue>,
+}
+
+// fn
+
+fn poll_ready<'a>(wakeup: &AsyncHandle, tasks: &Tasks<'a>, queue: &Arc<WakeQueue>) {
+    if tasks.polling.replace(true) {
+        return; // re-entrant spawn, the outer drain picks the task up
+    }
+
+    while let Some(id) = queue.pop() {
+        let task = tasks.pending.borrow_mut().remove(&id);
+        if let Some(mut task) = task {
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                queue: queue.clone(),
+            }));
+            if task
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_pending()
+            {
+                tasks.pending.borrow_mut().insert(id, task);
+            }
+        }
+    }
+
+    // pending tasks keep the loop alive, an idle executor must not
+    let mut handle = wakeup.into_handle();
+    if tasks.pending.borrow().is_empty() {
+        handle.unref();
+    } else {
+        handle.r#ref();
+    }
+
+    tasks.polling.set(false);
+}
+
+// impl
+
+impl WakeQueue {
+    fn push(&self, id: usize) {
+        self.ready.lock().unwrap().push_back(id);
+        if let Some(sender) = *self.sender.lock().unwrap() {
+            let _ = sender.send();
+        }
+    }
+
+    fn pop(&self) -> Option<usize> {
+        self.ready.lock().unwrap().pop_front()
+    }
+}
+
+impl<'a> Executor<'a> {
+    fn new(r#loop: &Loop) -> Result<Self, Errno> {
+        let tasks = Rc::new(Tasks {
+            pending: RefCell::new(HashMap::new()),
+            next_id: Cell::new(0),
+            polling: Cell::new(false),
+        });
+        let queue = Arc::new(WakeQueue {
+            ready: Mutex::new(VecDeque::new()),
+            sender: Mutex::new(None),
+        });
+
+        let wakeup = r#loop.new_async({
+            let tasks = tasks.clone();
+            let queue = queue.clone();
+            move |handle: &AsyncHandle| poll_ready(handle, &tasks, &queue)
+        })?;
+        *queue.sender.lock().unwrap() = Some(wakeup.sender());
+        wakeup.into_handle().unref();
+
+        Ok(Self {
+            wakeup,
+            tasks,
+            queue,
+        })
+    }
+
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + 'a,
+    {
+        let id = self.tasks.next_id.get();
+        self.tasks.next_id.set(id + 1);
+        self.tasks.pending.borrow_mut().insert(id, Box::pin(future));
+
+        self.queue.push(id);
+        poll_ready(&self.wakeup, &self.tasks, &self.queue);
+    }
+
+    pub fn pending(&self) -> usize {
+        self.tasks.pending.borrow().len()
+    }
+
+    pub fn close(self) {
+        self.queue.sender.lock().unwrap().take();
+        self.tasks.pending.borrow_mut().clear();
+        self.wakeup.into_handle().close(());
+    }
+}
+
+impl Loop {
+    pub fn new_executor<'a>(&self) -> Result<Executor<'a>, Errno> {
+        Executor::new(self)
+    }
+
+    pub fn block_on<'a, F>(&self, future: F) -> Result<F::Output, Errno>
+    where
+        F: Future + 'a,
+        F::Output: 'a,
+    {
+        let executor = self.new_executor()?;
+        let output = Rc::new(RefCell::new(None));
+
+        let slot = output.clone();
+        executor.spawn(async move {
+            *slot.borrow_mut() = Some(future.await);
+        });
+
+        let mut r#loop = *self;
+        r#loop.run(RunMode::DEFAULT)?;
+
+        executor.close();
+        r#loop.run(RunMode::NOWAIT)?;
+
+        output.take().ok_or(Errno::ECANCELED)
+    }
+}
+
+// trait
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.queue.push(self.id);
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.queue.push(self.id);
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+// type
+
+struct CompletionState<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    completed: bool,
+}
+
+pub struct Completion<T> {
+    state: Rc<RefCell<CompletionState<T>>>,
+}
+
+pub(crate) struct Completer<T> {
+    state: Rc<RefCell<CompletionState<T>>>,
+}
+
+// fn
+
+pub(crate) fn completion<T>() -> (Completer<T>, Completion<T>) {
+    let state = Rc::new(RefCell::new(CompletionState {
+        value: None,
+        waker: None,
+        completed: false,
+    }));
+    (
+        Completer {
+            state: state.clone(),
+        },
+        Completion { state },
+    )
+}
+
+// impl
+
+impl<T> Completion<T> {
+    pub fn ready(value: T) -> Self {
+        let (completer, completion) = completion();
+        completer.complete(value);
+        completion
+    }
+}
+
+impl<T> Completer<T> {
+    pub fn complete(&self, value: T) {
+        let waker = {
+            let mut state = self.state.borrow_mut();
+            if state.completed {
+                return;
+            }
+            state.completed = true;
+            state.value = Some(value);
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+// trait
+
+impl<T> Future for Completion<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+        match state.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Clone for Completer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
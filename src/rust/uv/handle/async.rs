@@ -0,0 +1,164 @@
+use std::alloc::{Layout, alloc, dealloc};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        AllocCallback, CloseCallback, Errno, IHandle, Loop, UserData, uv_async_init, uv_async_send,
+        uv_async_t, uv_handle_t,
+    },
+};
+
+// super
+
+impl<'a> super::IHandleContext<'a> for AsyncContext<'a> {
+    fn into_handle_context(self) -> super::HandleContext<'a> {
+        super::HandleContext::from(self)
+    }
+}
+
+impl super::IHandle for AsyncHandle {
+    fn into_handle(self) -> super::Handle {
+        super::Handle::from_inner(self.raw as *mut uv_handle_t)
+    }
+
+    fn drop_handle(self) {
+        let layout = Layout::new::<uv_async_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+// type
+
+pub struct AsyncCallback<'a>(pub Box<dyn FnMut(&'a AsyncHandle) + 'a>);
+
+#[derive(Default)]
+#[repr(C)]
+pub struct AsyncContext<'a> {
+    alloc_cb: Option<AllocCallback<'a>>,
+    close_cb: Option<CloseCallback<'a>>,
+    data: Option<UserData>,
+    async_cb: Option<AsyncCallback<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncHandle {
+    raw: *mut uv_async_t,
+}
+
+// NOTE: uv_async_send is the only thread-safe libuv call, the handle must outlive every send
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncSender {
+    raw: *mut uv_async_t,
+}
+
+unsafe impl Send for AsyncSender {}
+unsafe impl Sync for AsyncSender {}
+
+// fn
+
+pub(crate) unsafe extern "C" fn uv_async_cb(handle: *mut uv_async_t) {
+    let handle = AsyncHandle::from_inner(handle);
+    if let Some(context) = handle.into_handle().get_context::<AsyncContext>() {
+        if let Some(ref mut async_cb) = context.async_cb {
+            async_cb.0(&handle);
+        }
+    }
+}
+
+// impl
+
+impl AsyncHandle {
+    fn new<'a, ACB>(r#loop: &Loop, async_cb: ACB) -> Result<Self, Errno>
+    where
+        ACB: Into<AsyncCallback<'a>>,
+    {
+        let layout = Layout::new::<uv_async_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_async_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_handle(raw as *mut uv_handle_t);
+
+        let result = unsafe { uv_async_init(r#loop.into_inner(), raw, Some(uv_async_cb)) };
+        if result < 0 {
+            unsafe { dealloc(raw as *mut u8, layout) };
+            return Err(Errno::from_inner(result));
+        }
+
+        let handle = Self { raw };
+        handle.into_handle().set_context(AsyncContext {
+            alloc_cb: None,
+            close_cb: None,
+            data: None,
+            async_cb: Some(async_cb.into()),
+        });
+
+        Ok(handle)
+    }
+
+    pub fn send(&self) -> Result<(), Errno> {
+        result!(unsafe { uv_async_send(self.raw) })
+    }
+
+    pub fn sender(&self) -> AsyncSender {
+        AsyncSender { raw: self.raw }
+    }
+}
+
+impl AsyncSender {
+    pub fn send(&self) -> Result<(), Errno> {
+        result!(unsafe { uv_async_send(self.raw) })
+    }
+}
+
+impl Loop {
+    pub fn new_async<'a, ACB>(&self, async_cb: ACB) -> Result<AsyncHandle, Errno>
+    where
+        ACB: Into<AsyncCallback<'a>>,
+    {
+        return AsyncHandle::new(self, async_cb);
+    }
+}
+
+// trait
+
+impl<'a> From<AsyncContext<'a>> for super::HandleContext<'a> {
+    fn from(value: AsyncContext<'a>) -> Self {
+        Self {
+            alloc_cb: value.alloc_cb,
+            close_cb: value.close_cb,
+            data: value.data,
+        }
+    }
+}
+
+impl<'a, Fn> From<Fn> for AsyncCallback<'a>
+where
+    Fn: FnMut(&AsyncHandle) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for AsyncCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_| ()))
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_async_t> for AsyncHandle {
+    fn from_inner(raw: *mut uv_async_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_async_t> for AsyncHandle {
+    fn into_inner(self) -> *mut uv_async_t {
+        self.raw
+    }
+}
@@ -1,16 +1,11 @@
-use std::{
-    alloc::{Layout, alloc, dealloc},
-    any::{Any, TypeId},
-    os::raw::c_void,
-    ptr::null_mut,
-};
+use std::alloc::{Layout, alloc, dealloc};
 
 use crate::{
     inners::{FromInner, IntoInner},
     result,
     uv::{
-        AllocCallback, CloseCallback, Errno, IHandle, Loop, uv_check_init, uv_check_start,
-        uv_check_stop, uv_check_t, uv_handle_t,
+        AllocCallback, CloseCallback, Errno, IHandle, Loop, UserData, uv_check_init,
+        uv_check_start, uv_check_stop, uv_check_t, uv_handle_t,
     },
 };
 
@@ -37,11 +32,12 @@ impl super::IHandle for CheckHandle {
 
 pub struct CheckCallback<'a>(pub Box<dyn FnMut(&'a CheckHandle) + 'a>);
 
+#[derive(Default)]
 #[repr(C)]
 pub struct CheckContext<'a> {
     alloc_cb: Option<AllocCallback<'a>>,
     close_cb: Option<CloseCallback<'a>>,
-    data: *mut c_void,
+    data: Option<UserData>,
     check_cb: Option<CheckCallback<'a>>,
 }
 
@@ -95,7 +91,7 @@ impl CheckHandle {
                 handle.set_context(CheckContext {
                     alloc_cb: None,
                     close_cb: None,
-                    data: null_mut(),
+                    data: None,
                     check_cb: Some(check_cb.into()),
                 });
             }
@@ -107,39 +103,6 @@ impl CheckHandle {
     pub fn stop(&mut self) {
         unsafe { uv_check_stop(self.raw) };
     }
-
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        let handle = self.into_handle();
-        if let Some(context) = unsafe { handle.get_context::<CheckContext>() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "CheckHandle::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
-        } else {
-            None
-        }
-    }
-
-    pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
-        let mut handle = self.into_handle();
-        match unsafe { handle.get_context::<CheckContext>() } {
-            Some(context) => context.data = data,
-            None => {
-                handle.set_context(CheckContext {
-                    alloc_cb: None,
-                    close_cb: None,
-                    data,
-                    check_cb: None,
-                });
-            }
-        }
-    }
 }
 
 impl Loop {
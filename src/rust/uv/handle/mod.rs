@@ -3,23 +3,37 @@
 pub(crate) mod check;
 pub(crate) use check::*;
 
+pub(crate) mod r#async;
+pub(crate) use r#async::*;
+
+pub(crate) mod timer;
+pub(crate) use timer::*;
+
+pub(crate) mod signal;
+pub(crate) use signal::*;
+
 pub(crate) mod stream;
 pub(crate) use stream::*;
 
+pub(crate) mod typed;
+pub(crate) use typed::*;
+
 use std::{
-    any::{Any, TypeId},
     ffi::CStr,
     fmt::Display,
-    os::raw::c_void,
+    os::raw::{c_int, c_void},
     ptr::{copy_nonoverlapping, null_mut},
 };
 
 use crate::{
     inners::{FromInner, IntoInner},
+    result,
     uv::{
-        self, Buf, Loop, uv_buf_t, uv_check_t, uv_close, uv_handle_get_data, uv_handle_get_loop,
-        uv_handle_get_type, uv_handle_set_data, uv_handle_t, uv_handle_type, uv_handle_type_name,
-        uv_is_active, uv_is_closing, uv_stream_t,
+        self, Buf, Errno, Loop, UserData, uv_async_t, uv_buf_t, uv_check_t, uv_close, uv_fileno,
+        uv_handle_get_data, uv_handle_get_loop, uv_handle_get_type, uv_handle_set_data,
+        uv_handle_size, uv_handle_t, uv_handle_type, uv_handle_type_name, uv_has_ref, uv_is_active,
+        uv_is_closing, uv_os_fd_t, uv_recv_buffer_size, uv_ref, uv_send_buffer_size, uv_signal_t,
+        uv_stream_t, uv_timer_t, uv_unref,
     },
 };
 
@@ -52,11 +66,12 @@ pub enum HandleType {
 pub struct AllocCallback<'a>(pub Box<dyn FnMut(&'a Handle, usize) -> Option<Buf> + 'a>);
 pub struct CloseCallback<'a>(pub Box<dyn FnMut(&'a Handle) + 'a>);
 
+#[derive(Default)]
 #[repr(C)]
 pub struct HandleContext<'a> {
     alloc_cb: Option<AllocCallback<'a>>,
     close_cb: Option<CloseCallback<'a>>,
-    data: *mut c_void,
+    data: Option<UserData>,
 }
 
 pub trait IHandleContext<'a> {
@@ -95,6 +110,42 @@ pub trait IHandle: Copy {
     fn get_type(&self) -> HandleType {
         self.into_handle().get_type()
     }
+
+    fn r#ref(&mut self) {
+        self.into_handle().r#ref()
+    }
+
+    fn unref(&mut self) {
+        self.into_handle().unref()
+    }
+
+    fn has_ref(&self) -> bool {
+        self.into_handle().has_ref()
+    }
+
+    fn fileno(&self) -> Result<i32, Errno> {
+        self.into_handle().fileno()
+    }
+
+    fn send_buffer_size(&self) -> Result<i32, Errno> {
+        self.into_handle().send_buffer_size()
+    }
+
+    fn set_send_buffer_size(&mut self, size: i32) -> Result<(), Errno> {
+        self.into_handle().set_send_buffer_size(size)
+    }
+
+    fn recv_buffer_size(&self) -> Result<i32, Errno> {
+        self.into_handle().recv_buffer_size()
+    }
+
+    fn set_recv_buffer_size(&mut self, size: i32) -> Result<(), Errno> {
+        self.into_handle().set_recv_buffer_size(size)
+    }
+
+    fn size(&self) -> usize {
+        self.get_type().size()
+    }
 }
 
 // fn
@@ -143,6 +194,10 @@ impl HandleType {
             .to_string_lossy()
             .into_owned()
     }
+
+    pub fn size(&self) -> usize {
+        unsafe { uv_handle_size(self.into_inner()) }
+    }
 }
 
 impl<'a> IHandleContext<'a> for HandleContext<'a> {
@@ -169,11 +224,10 @@ impl Handle {
                 context.close_cb = Some(close_cb.into());
             }
             None => {
-                self.set_context(HandleContext {
-                    alloc_cb: None,
-                    close_cb: Some(close_cb.into()),
-                    data: null_mut(),
-                });
+                self.init_context();
+                if let Some(context) = unsafe { self.get_context::<HandleContext>() } {
+                    context.close_cb = Some(close_cb.into());
+                }
             }
         };
 
@@ -188,33 +242,91 @@ impl Handle {
         HandleType::from_inner(unsafe { uv_handle_get_type(self.raw) })
     }
 
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        if let Some(context) = unsafe { self.get_context::<HandleContext>() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "Handle::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
+    pub fn r#ref(&mut self) {
+        unsafe { uv_ref(self.raw) }
+    }
+
+    pub fn unref(&mut self) {
+        unsafe { uv_unref(self.raw) }
+    }
+
+    pub fn has_ref(&self) -> bool {
+        unsafe { uv_has_ref(self.raw) != 0 }
+    }
+
+    pub fn fileno(&self) -> Result<i32, Errno> {
+        let mut fd: uv_os_fd_t = -1;
+        let result = unsafe { uv_fileno(self.raw, &mut fd) };
+        if result < 0 {
+            Err(Errno::from_inner(result))
+        } else {
+            Ok(fd)
+        }
+    }
+
+    // NOTE: libuv reads the size when value is 0 and sets it otherwise, so setters reject 0
+    pub fn send_buffer_size(&self) -> Result<i32, Errno> {
+        let mut value: c_int = 0;
+        let result = unsafe { uv_send_buffer_size(self.raw, &mut value) };
+        if result < 0 {
+            Err(Errno::from_inner(result))
+        } else {
+            Ok(value)
+        }
+    }
+
+    pub fn set_send_buffer_size(&mut self, size: i32) -> Result<(), Errno> {
+        if size <= 0 {
+            return Err(Errno::EINVAL);
+        }
+
+        let mut value: c_int = size;
+        result!(unsafe { uv_send_buffer_size(self.raw, &mut value) })
+    }
+
+    pub fn recv_buffer_size(&self) -> Result<i32, Errno> {
+        let mut value: c_int = 0;
+        let result = unsafe { uv_recv_buffer_size(self.raw, &mut value) };
+        if result < 0 {
+            Err(Errno::from_inner(result))
         } else {
+            Ok(value)
+        }
+    }
+
+    pub fn set_recv_buffer_size(&mut self, size: i32) -> Result<(), Errno> {
+        if size <= 0 {
+            return Err(Errno::EINVAL);
+        }
+
+        let mut value: c_int = size;
+        result!(unsafe { uv_recv_buffer_size(self.raw, &mut value) })
+    }
+
+    pub(crate) fn user_data(&self) -> Option<&UserData> {
+        let context = unsafe { uv_handle_get_data(self.raw) } as *const HandleContext;
+        if context.is_null() {
             None
+        } else {
+            unsafe { (*context).data.as_ref() }
         }
     }
 
-    pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
-        match unsafe { self.get_context::<HandleContext>() } {
-            Some(context) => context.data = data,
-            None => {
-                self.set_context(HandleContext {
-                    alloc_cb: None,
-                    close_cb: None,
-                    data,
-                });
-            }
+    pub(crate) fn user_data_mut(&mut self) -> Option<&mut UserData> {
+        let context = unsafe { uv_handle_get_data(self.raw) } as *mut HandleContext;
+        if context.is_null() {
+            None
+        } else {
+            unsafe { (*context).data.as_mut() }
+        }
+    }
+
+    pub(crate) fn set_user_data(&mut self, data: Option<UserData>) {
+        if unsafe { self.get_context::<HandleContext>() }.is_none() {
+            self.init_context();
+        }
+        if let Some(context) = unsafe { self.get_context::<HandleContext>() } {
+            context.data = data;
         }
     }
 
@@ -231,13 +343,37 @@ impl Handle {
         unsafe { uv_handle_set_data(self.raw, Box::into_raw(Box::new(context)) as *mut c_void) };
     }
 
+    // NOTE: every handle type owns exactly one context type, created and dropped as that type
+    pub(crate) fn init_context(&mut self) {
+        match self.get_type() {
+            HandleType::ASYNC => self.set_context(AsyncContext::default()),
+            HandleType::CHECK => self.set_context(CheckContext::default()),
+            HandleType::TIMER => self.set_context(TimerContext::default()),
+            HandleType::SIGNAL => self.set_context(SignalContext::default()),
+            HandleType::STREAM | HandleType::TCP | HandleType::TTY | HandleType::NAMED_PIPE => {
+                self.set_context(StreamContext::default())
+            }
+            _ => self.set_context(HandleContext::default()),
+        }
+    }
+
     pub(crate) fn drop_context(&mut self) {
-        if let Some(context) = unsafe { self.get_context::<HandleContext>() } {
-            if !context.data.is_null() {
-                drop(unsafe { Box::from_raw(context.data) })
+        let context = unsafe { uv_handle_get_data(self.raw) };
+        if context.is_null() {
+            return;
+        }
+
+        match self.get_type() {
+            HandleType::ASYNC => drop(unsafe { Box::from_raw(context as *mut AsyncContext) }),
+            HandleType::CHECK => drop(unsafe { Box::from_raw(context as *mut CheckContext) }),
+            HandleType::TIMER => drop(unsafe { Box::from_raw(context as *mut TimerContext) }),
+            HandleType::SIGNAL => drop(unsafe { Box::from_raw(context as *mut SignalContext) }),
+            HandleType::STREAM | HandleType::TCP | HandleType::TTY | HandleType::NAMED_PIPE => {
+                drop(unsafe { Box::from_raw(context as *mut StreamContext) })
             }
-            drop(unsafe { Box::from_raw(context) })
+            _ => drop(unsafe { Box::from_raw(context as *mut HandleContext) }),
         }
+        unsafe { uv_handle_set_data(self.raw, null_mut()) };
     }
 }
 
@@ -248,8 +384,13 @@ impl IHandle for Handle {
 
     fn drop_handle(self) {
         match self.get_type() {
+            HandleType::ASYNC => AsyncHandle::from_inner(self.raw as *mut uv_async_t).drop_handle(),
             HandleType::CHECK => CheckHandle::from_inner(self.raw as *mut uv_check_t).drop_handle(),
-            HandleType::STREAM => {
+            HandleType::TIMER => TimerHandle::from_inner(self.raw as *mut uv_timer_t).drop_handle(),
+            HandleType::SIGNAL => {
+                SignalHandle::from_inner(self.raw as *mut uv_signal_t).drop_handle()
+            }
+            HandleType::STREAM | HandleType::TCP | HandleType::TTY | HandleType::NAMED_PIPE => {
                 StreamHandle::from_inner(self.raw as *mut uv_stream_t).drop_handle()
             }
             _ => panic!(
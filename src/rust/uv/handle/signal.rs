@@ -0,0 +1,168 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    ffi::c_int,
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        AllocCallback, CloseCallback, Errno, IHandle, Loop, UserData, uv_handle_t, uv_signal_init,
+        uv_signal_start, uv_signal_start_oneshot, uv_signal_stop, uv_signal_t,
+    },
+};
+
+// super
+
+impl<'a> super::IHandleContext<'a> for SignalContext<'a> {
+    fn into_handle_context(self) -> super::HandleContext<'a> {
+        super::HandleContext::from(self)
+    }
+}
+
+impl super::IHandle for SignalHandle {
+    fn into_handle(self) -> super::Handle {
+        super::Handle::from_inner(self.raw as *mut uv_handle_t)
+    }
+
+    fn drop_handle(self) {
+        let layout = Layout::new::<uv_signal_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+// type
+
+pub struct SignalCallback<'a>(pub Box<dyn FnMut(&'a SignalHandle, i32) + 'a>);
+
+#[derive(Default)]
+#[repr(C)]
+pub struct SignalContext<'a> {
+    alloc_cb: Option<AllocCallback<'a>>,
+    close_cb: Option<CloseCallback<'a>>,
+    data: Option<UserData>,
+    signal_cb: Option<SignalCallback<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SignalHandle {
+    raw: *mut uv_signal_t,
+}
+
+// fn
+
+pub(crate) unsafe extern "C" fn uv_signal_cb(handle: *mut uv_signal_t, signum: c_int) {
+    let handle = SignalHandle::from_inner(handle);
+    if let Some(context) = handle.into_handle().get_context::<SignalContext>() {
+        if let Some(ref mut signal_cb) = context.signal_cb {
+            signal_cb.0(&handle, signum);
+        }
+    }
+}
+
+// impl
+
+impl SignalHandle {
+    fn new(r#loop: &Loop) -> Result<Self, Errno> {
+        let layout = Layout::new::<uv_signal_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_signal_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_handle(raw as *mut uv_handle_t);
+
+        let result = unsafe { uv_signal_init(r#loop.into_inner(), raw) };
+        if result < 0 {
+            unsafe { dealloc(raw as *mut u8, layout) };
+            return Err(Errno::from_inner(result));
+        }
+
+        Ok(Self { raw })
+    }
+
+    pub fn start<'a, SCB>(&mut self, signal_cb: SCB, signum: i32) -> Result<(), Errno>
+    where
+        SCB: Into<SignalCallback<'a>>,
+    {
+        self.set_callback(signal_cb.into());
+        result!(unsafe { uv_signal_start(self.raw, Some(uv_signal_cb), signum as c_int) })
+    }
+
+    // NOTE: the handle stops itself before the callback runs
+    pub fn start_oneshot<'a, SCB>(&mut self, signal_cb: SCB, signum: i32) -> Result<(), Errno>
+    where
+        SCB: Into<SignalCallback<'a>>,
+    {
+        self.set_callback(signal_cb.into());
+        result!(unsafe { uv_signal_start_oneshot(self.raw, Some(uv_signal_cb), signum as c_int) })
+    }
+
+    pub fn stop(&mut self) -> Result<(), Errno> {
+        result!(unsafe { uv_signal_stop(self.raw) })
+    }
+
+    fn set_callback<'a>(&mut self, signal_cb: SignalCallback<'a>) {
+        let mut handle = self.into_handle();
+        match unsafe { handle.get_context::<SignalContext>() } {
+            Some(ref mut context) => {
+                context.signal_cb = Some(signal_cb);
+            }
+            None => {
+                handle.set_context(SignalContext {
+                    alloc_cb: None,
+                    close_cb: None,
+                    data: None,
+                    signal_cb: Some(signal_cb),
+                });
+            }
+        };
+    }
+}
+
+impl Loop {
+    pub fn new_signal(&self) -> Result<SignalHandle, Errno> {
+        return SignalHandle::new(self);
+    }
+}
+
+// trait
+
+impl<'a> From<SignalContext<'a>> for super::HandleContext<'a> {
+    fn from(value: SignalContext<'a>) -> Self {
+        Self {
+            alloc_cb: value.alloc_cb,
+            close_cb: value.close_cb,
+            data: value.data,
+        }
+    }
+}
+
+impl<'a, Fn> From<Fn> for SignalCallback<'a>
+where
+    Fn: FnMut(&SignalHandle, i32) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for SignalCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_signal_t> for SignalHandle {
+    fn from_inner(raw: *mut uv_signal_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_signal_t> for SignalHandle {
+    fn into_inner(self) -> *mut uv_signal_t {
+        self.raw
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io::{self, Read, Write},
+    rc::Rc,
+};
+
+use crate::uv::{Buf, Errno, Handle, IStreamHandle, StreamHandle, WriteRequest, dealloc_base};
+
+// type
+
+struct WriterState {
+    pending: usize,
+    error: Option<Errno>,
+}
+
+pub struct StreamWriter {
+    stream: StreamHandle,
+    state: Rc<RefCell<WriterState>>,
+}
+
+struct ReaderState {
+    buffer: VecDeque<u8>,
+    eof: bool,
+    error: Option<Errno>,
+}
+
+pub struct StreamReader {
+    stream: StreamHandle,
+    state: Rc<RefCell<ReaderState>>,
+}
+
+// impl
+
+impl StreamWriter {
+    pub fn new<S: IStreamHandle>(stream: S) -> Self {
+        Self {
+            stream: stream.into_stream(),
+            state: Rc::new(RefCell::new(WriterState {
+                pending: 0,
+                error: None,
+            })),
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.state.borrow().pending
+    }
+
+    fn take_error(&self) -> io::Result<()> {
+        match self.state.borrow_mut().error.take() {
+            Some(err) => Err(io::Error::from(err)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl StreamReader {
+    pub fn new<S: IStreamHandle>(stream: S) -> Result<Self, Errno> {
+        let mut stream = stream.into_stream();
+        let state = Rc::new(RefCell::new(ReaderState {
+            buffer: VecDeque::new(),
+            eof: false,
+            error: None,
+        }));
+
+        let on_read = state.clone();
+        stream.read_start(
+            |_: &Handle, suggested_size| Some(Buf::new_with_len(suggested_size)),
+            move |_: &StreamHandle, nread: Result<isize, Errno>, buf: Buf| {
+                let mut state = on_read.borrow_mut();
+                match nread {
+                    Ok(len) => state.buffer.extend(&buf.as_bytes()[..len as usize]),
+                    Err(Errno::EOF) => state.eof = true,
+                    Err(err) => state.error = Some(err),
+                }
+                if buf.is_initialized() {
+                    unsafe { dealloc_base(buf.base(), buf.len()) };
+                }
+            },
+        )?;
+
+        Ok(Self { stream, state })
+    }
+
+    pub fn available(&self) -> usize {
+        self.state.borrow().buffer.len()
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.state.borrow().eof
+    }
+
+    pub fn stop(mut self) {
+        self.stream.read_stop();
+    }
+}
+
+// trait
+
+impl Write for StreamWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.take_error()?;
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        let buf = Buf::new_with_len(data.len());
+        buf.as_bytes_mut().copy_from_slice(data);
+
+        let state = self.state.clone();
+        let result = self.stream.write(
+            WriteRequest::new(),
+            &[buf],
+            move |_: WriteRequest, status: Result<(), Errno>| {
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+                let mut state = state.borrow_mut();
+                state.pending -= buf.len();
+                if let Err(err) = status {
+                    state.error.get_or_insert(err);
+                }
+            },
+        );
+
+        match result {
+            Ok(()) => {
+                self.state.borrow_mut().pending += data.len();
+                Ok(data.len())
+            }
+            Err(err) => {
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+                Err(io::Error::from(err))
+            }
+        }
+    }
+
+    // NOTE: writes are queued on the loop, flush only surfaces failures reported so far
+    fn flush(&mut self) -> io::Result<()> {
+        self.take_error()
+    }
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+        if !state.buffer.is_empty() || buf.is_empty() {
+            return state.buffer.read(buf);
+        }
+
+        match state.error.take() {
+            Some(err) => Err(io::Error::from(err)),
+            None if state.eof => Ok(0),
+            None => Err(io::ErrorKind::WouldBlock.into()),
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use std::{cell::RefCell, rc::Rc, str::from_utf8};
+
+use crate::uv::{
+    Buf, Completion, Errno, Handle, IStreamHandle, StreamHandle, WriteCallback, WriteRequest,
+    completion, dealloc_base,
+};
+
+// type
+
+pub trait Decoder {
+    type Item;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno>;
+
+    // NOTE: called once the peer is done writing, trailing bytes that never form a frame are an error
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        match self.decode(buffer)? {
+            Some(item) => Ok(Some(item)),
+            None if buffer.is_empty() => Ok(None),
+            None => Err(Errno::EPROTO),
+        }
+    }
+}
+
+pub trait Encoder<Item> {
+    fn encode(&mut self, item: Item, buffer: &mut Vec<u8>) -> Result<(), Errno>;
+}
+
+pub trait Codec: Decoder + Encoder<<Self as Decoder>::Item> {}
+
+#[derive(Debug, Clone, Default)]
+pub struct LinesCodec {
+    max_length: Option<usize>,
+    next_index: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LengthDelimitedCodec {
+    max_frame_length: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DelimiterCodec {
+    delimiter: Vec<u8>,
+    max_length: Option<usize>,
+    next_index: usize,
+}
+
+pub struct FrameCallback<'a, T>(pub Box<dyn FnMut(&StreamHandle, Result<T, Errno>) + 'a>);
+
+struct FramedState<C> {
+    codec: C,
+    buffer: Vec<u8>,
+    reading: bool,
+}
+
+pub struct FramedStream<C: Decoder> {
+    stream: StreamHandle,
+    state: Rc<RefCell<FramedState<C>>>,
+}
+
+// fn
+
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn take_frame(
+    buffer: &mut Vec<u8>,
+    delimiter: &[u8],
+    max_length: Option<usize>,
+    next_index: &mut usize,
+) -> Result<Option<Vec<u8>>, Errno> {
+    // resume just before the last partial delimiter instead of rescanning the whole buffer
+    let start = (*next_index + 1).saturating_sub(delimiter.len());
+    match find(&buffer[start..], delimiter) {
+        Some(offset) => {
+            let end = start + offset;
+            *next_index = 0;
+            if max_length.is_some_and(|max_length| end > max_length) {
+                buffer.drain(..end + delimiter.len());
+                return Err(Errno::EMSGSIZE);
+            }
+
+            let frame = buffer[..end].to_vec();
+            buffer.drain(..end + delimiter.len());
+            Ok(Some(frame))
+        }
+        None => {
+            *next_index = buffer.len();
+            if max_length.is_some_and(|max_length| buffer.len() > max_length + delimiter.len()) {
+                return Err(Errno::EMSGSIZE);
+            }
+            Ok(None)
+        }
+    }
+}
+
+// impl
+
+impl LinesCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_length(max_length: usize) -> Self {
+        Self {
+            max_length: Some(max_length),
+            next_index: 0,
+        }
+    }
+}
+
+impl LengthDelimitedCodec {
+    pub fn new() -> Self {
+        Self::with_max_frame_length(u32::MAX as usize)
+    }
+
+    pub fn with_max_frame_length(max_frame_length: usize) -> Self {
+        Self { max_frame_length }
+    }
+}
+
+impl DelimiterCodec {
+    pub fn new(delimiter: &[u8]) -> Result<Self, Errno> {
+        if delimiter.is_empty() {
+            return Err(Errno::EINVAL);
+        }
+        Ok(Self {
+            delimiter: delimiter.to_vec(),
+            max_length: None,
+            next_index: 0,
+        })
+    }
+
+    pub fn with_max_length(delimiter: &[u8], max_length: usize) -> Result<Self, Errno> {
+        Ok(Self {
+            max_length: Some(max_length),
+            ..Self::new(delimiter)?
+        })
+    }
+}
+
+impl<C: Decoder> FramedStream<C> {
+    pub fn new<S: IStreamHandle>(stream: S, codec: C) -> Self {
+        Self {
+            stream: stream.into_stream(),
+            state: Rc::new(RefCell::new(FramedState {
+                codec,
+                buffer: Vec::new(),
+                reading: false,
+            })),
+        }
+    }
+
+    pub fn stream(&self) -> StreamHandle {
+        self.stream
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.state.borrow().buffer.len()
+    }
+
+    pub fn read_start<'a, FCB>(&mut self, frame_cb: FCB) -> Result<(), Errno>
+    where
+        C: 'a,
+        FCB: Into<FrameCallback<'a, C::Item>>,
+    {
+        let mut frame_cb = frame_cb.into();
+        let state = self.state.clone();
+        self.state.borrow_mut().reading = true;
+        self.stream.read_start(
+            |_: &Handle, suggested_size| Some(Buf::new_with_len(suggested_size)),
+            move |stream: &StreamHandle, nread: Result<isize, Errno>, buf: Buf| {
+                let eof = match nread {
+                    Ok(len) => {
+                        state
+                            .borrow_mut()
+                            .buffer
+                            .extend_from_slice(&buf.as_bytes()[..len as usize]);
+                        Ok(false)
+                    }
+                    Err(Errno::EOF) => Ok(true),
+                    Err(err) => Err(err),
+                };
+                if buf.is_initialized() {
+                    unsafe { dealloc_base(buf.base(), buf.len()) };
+                }
+                // a failed read ends the stream just like a broken frame does
+                let eof = match eof {
+                    Ok(eof) => eof,
+                    Err(err) => {
+                        state.borrow_mut().reading = false;
+                        stream.into_stream().read_stop();
+                        frame_cb.0(stream, Err(err));
+                        return;
+                    }
+                };
+
+                // the state is released before every callback so it may send or stop freely
+                loop {
+                    let frame = {
+                        let mut state = state.borrow_mut();
+                        let FramedState {
+                            codec,
+                            buffer,
+                            reading,
+                        } = &mut *state;
+                        if !*reading {
+                            return;
+                        }
+                        if eof {
+                            codec.decode_eof(buffer)
+                        } else {
+                            codec.decode(buffer)
+                        }
+                    };
+                    match frame {
+                        Ok(Some(item)) => frame_cb.0(stream, Ok(item)),
+                        Ok(None) => break,
+                        Err(err) => {
+                            state.borrow_mut().reading = false;
+                            stream.into_stream().read_stop();
+                            frame_cb.0(stream, Err(err));
+                            return;
+                        }
+                    }
+                }
+
+                if eof {
+                    frame_cb.0(stream, Err(Errno::EOF));
+                }
+            },
+        )
+    }
+
+    // NOTE: frames already buffered stay there for the next read_start or read
+    pub fn read_stop(&mut self) {
+        self.state.borrow_mut().reading = false;
+        self.stream.read_stop();
+    }
+
+    pub fn read(&mut self) -> Completion<Result<C::Item, Errno>>
+    where
+        C: 'static,
+        C::Item: 'static,
+    {
+        let (completer, completion) = completion();
+        let frame = {
+            let mut state = self.state.borrow_mut();
+            let FramedState { codec, buffer, .. } = &mut *state;
+            codec.decode(buffer)
+        };
+        match frame {
+            Ok(Some(item)) => completer.complete(Ok(item)),
+            Err(err) => completer.complete(Err(err)),
+            Ok(None) => {
+                let on_frame = completer.clone();
+                let state = self.state.clone();
+                if let Err(err) = self.read_start(
+                    move |stream: &StreamHandle, frame: Result<C::Item, Errno>| {
+                        state.borrow_mut().reading = false;
+                        stream.into_stream().read_stop();
+                        on_frame.complete(frame);
+                    },
+                ) {
+                    completer.complete(Err(err));
+                }
+            }
+        }
+        completion
+    }
+
+    pub fn send<'a, I, WCB>(&mut self, item: I, write_cb: WCB) -> Result<(), Errno>
+    where
+        C: Encoder<I>,
+        WCB: Into<WriteCallback<'a>>,
+    {
+        let mut encoded = Vec::new();
+        self.state.borrow_mut().codec.encode(item, &mut encoded)?;
+        if encoded.is_empty() {
+            return Ok(());
+        }
+
+        let buf = Buf::new_with_len(encoded.len());
+        buf.as_bytes_mut().copy_from_slice(&encoded);
+
+        let mut write_cb = write_cb.into();
+        let result = self.stream.write(
+            WriteRequest::new(),
+            &[buf],
+            move |req: WriteRequest, status: Result<(), Errno>| {
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+                write_cb.0(req, status);
+            },
+        );
+        if result.is_err() {
+            unsafe { dealloc_base(buf.base(), buf.len()) };
+        }
+        result
+    }
+
+    pub fn into_stream(self) -> StreamHandle {
+        self.stream
+    }
+}
+
+// trait
+
+impl<T> Codec for T where T: Decoder + Encoder<<T as Decoder>::Item> {}
+
+impl Decoder for LinesCodec {
+    type Item = String;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        match take_frame(buffer, b"\n", self.max_length, &mut self.next_index)? {
+            Some(mut line) => {
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                String::from_utf8(line).map(Some).map_err(|_| Errno::EILSEQ)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        match self.decode(buffer)? {
+            Some(line) => Ok(Some(line)),
+            None if buffer.is_empty() => Ok(None),
+            None => {
+                self.next_index = 0;
+                let line = from_utf8(buffer)
+                    .map(|line| line.trim_end_matches('\r').to_string())
+                    .map_err(|_| Errno::EILSEQ);
+                buffer.clear();
+                line.map(Some)
+            }
+        }
+    }
+}
+
+impl Encoder<String> for LinesCodec {
+    fn encode(&mut self, item: String, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        if self
+            .max_length
+            .is_some_and(|max_length| item.len() > max_length)
+        {
+            return Err(Errno::EMSGSIZE);
+        }
+        buffer.extend_from_slice(item.as_bytes());
+        buffer.push(b'\n');
+        Ok(())
+    }
+}
+
+impl Decoder for LengthDelimitedCodec {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        if buffer.len() < 4 {
+            return Ok(None);
+        }
+
+        let len = u32::from_be_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
+        if len > self.max_frame_length {
+            return Err(Errno::EMSGSIZE);
+        }
+        if buffer.len() < 4 + len {
+            return Ok(None);
+        }
+
+        let frame = buffer[4..4 + len].to_vec();
+        buffer.drain(..4 + len);
+        Ok(Some(frame))
+    }
+}
+
+impl Encoder<Vec<u8>> for LengthDelimitedCodec {
+    fn encode(&mut self, item: Vec<u8>, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        if item.len() > self.max_frame_length || item.len() > u32::MAX as usize {
+            return Err(Errno::EMSGSIZE);
+        }
+        buffer.extend_from_slice(&(item.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(&item);
+        Ok(())
+    }
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for DelimiterCodec {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        take_frame(
+            buffer,
+            &self.delimiter,
+            self.max_length,
+            &mut self.next_index,
+        )
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Self::Item>, Errno> {
+        match self.decode(buffer)? {
+            Some(frame) => Ok(Some(frame)),
+            None if buffer.is_empty() => Ok(None),
+            None => {
+                self.next_index = 0;
+                Ok(Some(buffer.drain(..).collect()))
+            }
+        }
+    }
+}
+
+impl Encoder<Vec<u8>> for DelimiterCodec {
+    fn encode(&mut self, item: Vec<u8>, buffer: &mut Vec<u8>) -> Result<(), Errno> {
+        if self
+            .max_length
+            .is_some_and(|max_length| item.len() > max_length)
+        {
+            return Err(Errno::EMSGSIZE);
+        }
+        buffer.extend_from_slice(&item);
+        buffer.extend_from_slice(&self.delimiter);
+        Ok(())
+    }
+}
+
+impl<'a, T, Fn> From<Fn> for FrameCallback<'a, T>
+where
+    Fn: FnMut(&StreamHandle, Result<T, Errno>) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a, T> From<()> for FrameCallback<'a, T> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
@@ -3,20 +3,29 @@
 pub(crate) mod tty;
 pub(crate) use tty::*;
 
-use std::{
-    any::{Any, TypeId},
-    os::raw::{c_int, c_void},
-    ptr::null_mut,
-};
+pub(crate) mod tcp;
+pub(crate) use tcp::*;
+
+pub(crate) mod pipe;
+pub(crate) use pipe::*;
+
+pub(crate) mod adapter;
+pub(crate) use adapter::*;
+
+pub(crate) mod codec;
+pub(crate) use codec::*;
+
+use std::os::raw::c_int;
 
 use crate::{
     inners::{FromInner, IntoInner},
     result,
     uv::{
-        AllocCallback, Buf, CloseCallback, Errno, Handle, IHandle, IRequest, ShutdownCallback,
-        ShutdownContext, ShutdownRequest, WriteCallback, WriteContext, WriteRequest, uv_accept,
-        uv_alloc_cb, uv_buf_t, uv_errno_t, uv_handle_t, uv_is_readable, uv_is_writable, uv_listen,
-        uv_read_start, uv_read_stop, uv_shutdown, uv_shutdown_cb, uv_stream_t, uv_tty_t, uv_write,
+        AllocCallback, Buf, CloseCallback, Completion, Errno, Handle, IHandle, IRequest,
+        ShutdownCallback, ShutdownContext, ShutdownRequest, UserData, WriteCallback, WriteContext,
+        WriteRequest, completion, dealloc_base, uv_accept, uv_alloc_cb, uv_buf_t, uv_errno_t,
+        uv_handle_t, uv_is_readable, uv_is_writable, uv_listen, uv_pipe_t, uv_read_start,
+        uv_read_stop, uv_shutdown, uv_shutdown_cb, uv_stream_t, uv_tcp_t, uv_tty_t, uv_write,
         uv_write_cb,
     },
 };
@@ -44,11 +53,12 @@ impl super::IHandle for StreamHandle {
 pub struct ConnectionCallback<'a>(pub Box<dyn FnMut(&'a StreamHandle, Result<(), Errno>) + 'a>);
 pub struct ReadCallback<'a>(pub Box<dyn FnMut(&'a StreamHandle, Result<isize, Errno>, Buf) + 'a>);
 
+#[derive(Default)]
 #[repr(C)]
 pub struct StreamContext<'a> {
     alloc_cb: Option<AllocCallback<'a>>,
     close_cb: Option<CloseCallback<'a>>,
-    data: *mut c_void,
+    data: Option<UserData>,
     connection_cb: Option<ConnectionCallback<'a>>,
     read_cb: Option<ReadCallback<'a>>,
 }
@@ -93,6 +103,10 @@ pub trait IStreamHandle: Copy {
         self.into_stream().read_stop()
     }
 
+    fn read(&mut self) -> Completion<Result<Vec<u8>, Errno>> {
+        self.into_stream().read()
+    }
+
     fn write<'a, WCB>(
         &mut self,
         req: WriteRequest,
@@ -168,7 +182,7 @@ impl StreamHandle {
                 context.shutdown_cb = Some(shutdown_cb.into());
             }
             None => request.set_context(ShutdownContext {
-                data: null_mut(),
+                data: None,
                 shutdown_cb: Some(shutdown_cb.into()),
             }),
         };
@@ -189,7 +203,7 @@ impl StreamHandle {
                 handle.set_context(StreamContext {
                     alloc_cb: None,
                     close_cb: None,
-                    data: null_mut(),
+                    data: None,
                     connection_cb: Some(connection_cb.into()),
                     read_cb: None,
                 });
@@ -218,7 +232,7 @@ impl StreamHandle {
                 handle.set_context(StreamContext {
                     alloc_cb: Some(alloc_cb.into()),
                     close_cb: None,
-                    data: null_mut(),
+                    data: None,
                     connection_cb: None,
                     read_cb: Some(read_cb.into()),
                 });
@@ -232,6 +246,34 @@ impl StreamHandle {
         unsafe { uv_read_stop(self.raw) };
     }
 
+    pub fn read(&mut self) -> Completion<Result<Vec<u8>, Errno>> {
+        let (completer, completion) = completion();
+        let on_read = completer.clone();
+        if let Err(err) = self.read_start(
+            |_: &Handle, suggested_size| Some(Buf::new_with_len(suggested_size)),
+            move |stream: &StreamHandle, nread: Result<isize, Errno>, buf: Buf| {
+                match nread {
+                    Ok(0) => {}
+                    Ok(len) => {
+                        stream.into_stream().read_stop();
+                        on_read.complete(Ok(buf.as_bytes()[..len as usize].to_vec()));
+                    }
+                    Err(err) => {
+                        stream.into_stream().read_stop();
+                        on_read.complete(Err(err));
+                    }
+                }
+                // the bytes were copied out above, the buffer from alloc_cb is ours to free
+                if buf.is_initialized() {
+                    unsafe { dealloc_base(buf.base(), buf.len()) };
+                }
+            },
+        ) {
+            completer.complete(Err(err));
+        }
+        completion
+    }
+
     pub fn write<'a, WCB>(
         &mut self,
         req: WriteRequest,
@@ -247,7 +289,7 @@ impl StreamHandle {
                 context.write_cb = Some(write_cb.into());
             }
             None => request.set_context(WriteContext {
-                data: null_mut(),
+                data: None,
                 write_cb: Some(write_cb.into()),
             }),
         };
@@ -271,40 +313,6 @@ impl StreamHandle {
     pub fn writable(&self) -> bool {
         unsafe { uv_is_writable(self.raw) != 0 }
     }
-
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        let handle = self.into_handle();
-        if let Some(context) = unsafe { handle.get_context::<StreamContext>() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "StreamHandle::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
-        } else {
-            None
-        }
-    }
-
-    pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
-        let mut handle = self.into_handle();
-        match unsafe { handle.get_context::<StreamContext>() } {
-            Some(context) => context.data = data,
-            None => {
-                handle.set_context(StreamContext {
-                    alloc_cb: None,
-                    close_cb: None,
-                    data,
-                    connection_cb: None,
-                    read_cb: None,
-                });
-            }
-        }
-    }
 }
 
 impl IStreamHandle for StreamHandle {
@@ -317,6 +325,12 @@ impl IStreamHandle for StreamHandle {
             crate::uv::HandleType::TTY => {
                 TTYStream::from_inner(self.raw as *mut uv_tty_t).drop_stream()
             }
+            crate::uv::HandleType::TCP => {
+                TCPStream::from_inner(self.raw as *mut uv_tcp_t).drop_stream()
+            }
+            crate::uv::HandleType::NAMED_PIPE => {
+                PipeStream::from_inner(self.raw as *mut uv_pipe_t).drop_stream()
+            }
             _ => panic!(
                 "StreamHandle::drop_stream: unexpected type [{}]",
                 self.get_type().name()
@@ -0,0 +1,152 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    ffi::CString,
+    os::raw::c_int,
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        self, Completion, ConnectCallback, ConnectContext, ConnectRequest, Errno, IRequest,
+        IStreamHandle, Loop, completion, os, uv_connect_cb, uv_handle_t, uv_pipe_bind,
+        uv_pipe_connect, uv_pipe_getpeername, uv_pipe_getsockname, uv_pipe_init, uv_pipe_open,
+        uv_pipe_t, uv_stream_t,
+    },
+};
+
+// super
+
+impl super::IStreamHandle for PipeStream {
+    fn into_stream(self) -> super::StreamHandle {
+        super::StreamHandle::from_inner(self.raw as *mut uv_stream_t)
+    }
+
+    fn drop_stream(self) {
+        let layout = Layout::new::<uv_pipe_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+impl super::IHandle for PipeStream {
+    fn into_handle(self) -> uv::Handle {
+        super::Handle::from_inner(self.raw as *mut uv_handle_t)
+    }
+
+    fn drop_handle(self) {
+        self.drop_stream()
+    }
+}
+
+// type
+
+#[derive(Debug, Clone, Copy)]
+pub struct PipeStream {
+    raw: *mut uv_pipe_t,
+}
+
+// impl
+
+impl PipeStream {
+    fn new(r#loop: &Loop, ipc: bool) -> Result<Self, Errno> {
+        let layout = Layout::new::<uv_pipe_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_pipe_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_stream(raw as *mut uv_stream_t);
+
+        let result = unsafe { uv_pipe_init(r#loop.into_inner(), raw, ipc as c_int) };
+        if result < 0 {
+            unsafe { dealloc(raw as *mut u8, layout) };
+            return Err(Errno::from_inner(result));
+        }
+
+        Ok(Self { raw })
+    }
+
+    pub fn open(&mut self, fd: i32) -> Result<(), Errno> {
+        result!(unsafe { uv_pipe_open(self.raw, fd) })
+    }
+
+    pub fn bind(&mut self, name: &str) -> Result<(), Errno> {
+        let name = CString::new(name).map_err(|_| Errno::EINVAL)?;
+        result!(unsafe { uv_pipe_bind(self.raw, name.as_ptr()) })
+    }
+
+    pub fn connect<'a, CCB>(
+        &mut self,
+        req: ConnectRequest,
+        name: &str,
+        connect_cb: CCB,
+    ) -> Result<(), Errno>
+    where
+        CCB: Into<ConnectCallback<'a>>,
+    {
+        let name = CString::new(name).map_err(|_| Errno::EINVAL)?;
+
+        let mut request = req.into_request();
+        match unsafe { request.get_context::<ConnectContext>() } {
+            Some(context) => {
+                context.connect_cb = Some(connect_cb.into());
+            }
+            None => request.set_context(ConnectContext {
+                data: None,
+                connect_cb: Some(connect_cb.into()),
+            }),
+        };
+
+        // NOTE: uv_pipe_connect reports every failure through the callback
+        unsafe {
+            uv_pipe_connect(
+                req.into_inner(),
+                self.raw,
+                name.as_ptr(),
+                Some(uv_connect_cb),
+            )
+        };
+        Ok(())
+    }
+
+    pub fn connect_async(&mut self, name: &str) -> Completion<Result<(), Errno>> {
+        let (completer, completion) = completion();
+        let on_connect = completer.clone();
+        if let Err(err) = self.connect(
+            ConnectRequest::new(),
+            name,
+            move |_: ConnectRequest, status: Result<(), Errno>| on_connect.complete(status),
+        ) {
+            completer.complete(Err(err));
+        }
+        completion
+    }
+
+    pub fn get_sockname(&self) -> Result<String, Errno> {
+        os::read_string(|buffer, size| unsafe { uv_pipe_getsockname(self.raw, buffer, size) })
+    }
+
+    pub fn get_peername(&self) -> Result<String, Errno> {
+        os::read_string(|buffer, size| unsafe { uv_pipe_getpeername(self.raw, buffer, size) })
+    }
+}
+
+impl Loop {
+    pub fn new_pipe(&self, ipc: bool) -> Result<PipeStream, Errno> {
+        return PipeStream::new(self, ipc);
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_pipe_t> for PipeStream {
+    fn from_inner(raw: *mut uv_pipe_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_pipe_t> for PipeStream {
+    fn into_inner(self) -> *mut uv_pipe_t {
+        self.raw
+    }
+}
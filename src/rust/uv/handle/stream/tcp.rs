@@ -0,0 +1,174 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    mem::size_of,
+    net::SocketAddr,
+    os::raw::c_int,
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        self, Completion, ConnectCallback, ConnectContext, ConnectRequest, Errno, IRequest,
+        IStreamHandle, Loop, completion, from_sockaddr, sockaddr, sockaddr_storage, to_sockaddr,
+        uv_connect_cb, uv_handle_t, uv_stream_t, uv_tcp_bind, uv_tcp_connect, uv_tcp_getpeername,
+        uv_tcp_getsockname, uv_tcp_init, uv_tcp_keepalive, uv_tcp_nodelay, uv_tcp_t,
+    },
+};
+
+// super
+
+impl super::IStreamHandle for TCPStream {
+    fn into_stream(self) -> super::StreamHandle {
+        super::StreamHandle::from_inner(self.raw as *mut uv_stream_t)
+    }
+
+    fn drop_stream(self) {
+        let layout = Layout::new::<uv_tcp_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+impl super::IHandle for TCPStream {
+    fn into_handle(self) -> uv::Handle {
+        super::Handle::from_inner(self.raw as *mut uv_handle_t)
+    }
+
+    fn drop_handle(self) {
+        self.drop_stream()
+    }
+}
+
+// type
+
+#[derive(Debug, Clone, Copy)]
+pub struct TCPStream {
+    raw: *mut uv_tcp_t,
+}
+
+// impl
+
+impl TCPStream {
+    fn new(r#loop: &Loop) -> Result<Self, Errno> {
+        let layout = Layout::new::<uv_tcp_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_tcp_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_stream(raw as *mut uv_stream_t);
+
+        let result = unsafe { uv_tcp_init(r#loop.into_inner(), raw) };
+        if result < 0 {
+            unsafe { dealloc(raw as *mut u8, layout) };
+            return Err(Errno::from_inner(result));
+        }
+
+        Ok(Self { raw })
+    }
+
+    pub fn bind(&mut self, addr: &SocketAddr) -> Result<(), Errno> {
+        let addr = to_sockaddr(addr);
+        result!(unsafe { uv_tcp_bind(self.raw, &addr as *const _ as *const sockaddr, 0) })
+    }
+
+    pub fn connect<'a, CCB>(
+        &mut self,
+        req: ConnectRequest,
+        addr: &SocketAddr,
+        connect_cb: CCB,
+    ) -> Result<(), Errno>
+    where
+        CCB: Into<ConnectCallback<'a>>,
+    {
+        let mut request = req.into_request();
+        match unsafe { request.get_context::<ConnectContext>() } {
+            Some(context) => {
+                context.connect_cb = Some(connect_cb.into());
+            }
+            None => request.set_context(ConnectContext {
+                data: None,
+                connect_cb: Some(connect_cb.into()),
+            }),
+        };
+
+        let addr = to_sockaddr(addr);
+        result!(unsafe {
+            uv_tcp_connect(
+                req.into_inner(),
+                self.raw,
+                &addr as *const _ as *const sockaddr,
+                Some(uv_connect_cb),
+            )
+        })
+    }
+
+    pub fn connect_async(&mut self, addr: &SocketAddr) -> Completion<Result<(), Errno>> {
+        let (completer, completion) = completion();
+        let on_connect = completer.clone();
+        if let Err(err) = self.connect(
+            ConnectRequest::new(),
+            addr,
+            move |_: ConnectRequest, status: Result<(), Errno>| on_connect.complete(status),
+        ) {
+            completer.complete(Err(err));
+        }
+        completion
+    }
+
+    pub fn nodelay(&mut self, enable: bool) -> Result<(), Errno> {
+        result!(unsafe { uv_tcp_nodelay(self.raw, enable as c_int) })
+    }
+
+    pub fn keepalive(&mut self, enable: bool, delay: u32) -> Result<(), Errno> {
+        result!(unsafe { uv_tcp_keepalive(self.raw, enable as c_int, delay) })
+    }
+
+    pub fn get_sockname(&self) -> Result<SocketAddr, Errno> {
+        let mut storage: sockaddr_storage = unsafe { std::mem::zeroed() };
+        let mut len = size_of::<sockaddr_storage>() as c_int;
+        let result = unsafe {
+            uv_tcp_getsockname(self.raw, &mut storage as *mut _ as *mut sockaddr, &mut len)
+        };
+        if result < 0 {
+            Err(Errno::from_inner(result))
+        } else {
+            unsafe { from_sockaddr(&storage as *const _ as *const sockaddr) }
+                .ok_or(Errno::EAFNOSUPPORT)
+        }
+    }
+
+    pub fn get_peername(&self) -> Result<SocketAddr, Errno> {
+        let mut storage: sockaddr_storage = unsafe { std::mem::zeroed() };
+        let mut len = size_of::<sockaddr_storage>() as c_int;
+        let result = unsafe {
+            uv_tcp_getpeername(self.raw, &mut storage as *mut _ as *mut sockaddr, &mut len)
+        };
+        if result < 0 {
+            Err(Errno::from_inner(result))
+        } else {
+            unsafe { from_sockaddr(&storage as *const _ as *const sockaddr) }
+                .ok_or(Errno::EAFNOSUPPORT)
+        }
+    }
+}
+
+impl Loop {
+    pub fn new_tcp(&self) -> Result<TCPStream, Errno> {
+        return TCPStream::new(self);
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_tcp_t> for TCPStream {
+    fn from_inner(raw: *mut uv_tcp_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_tcp_t> for TCPStream {
+    fn into_inner(self) -> *mut uv_tcp_t {
+        self.raw
+    }
+}
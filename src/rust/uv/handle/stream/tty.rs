@@ -1,16 +1,15 @@
 use std::{
     alloc::{Layout, alloc, dealloc},
-    any::{Any, TypeId},
-    os::raw::{c_int, c_void},
+    os::raw::c_int,
 };
 
 use crate::{
     inners::{FromInner, IntoInner},
     result,
     uv::{
-        self, Errno, IHandle, IStreamHandle, Loop, StreamContext, uv_handle_t, uv_stream_t,
-        uv_tty_get_vterm_state, uv_tty_get_winsize, uv_tty_init, uv_tty_mode_t, uv_tty_reset_mode,
-        uv_tty_set_mode, uv_tty_set_vterm_state, uv_tty_t, uv_tty_vtermstate_t,
+        self, Errno, IStreamHandle, Loop, uv_handle_t, uv_stream_t, uv_tty_get_vterm_state,
+        uv_tty_get_winsize, uv_tty_init, uv_tty_mode_t, uv_tty_reset_mode, uv_tty_set_mode,
+        uv_tty_set_vterm_state, uv_tty_t, uv_tty_vtermstate_t,
     },
 };
 
@@ -59,6 +58,13 @@ pub struct TTYStream {
     raw: *mut uv_tty_t,
 }
 
+// fn
+
+// NOTE: uv keeps the mode it replaced process wide, so this is safe to call from a panic hook
+pub fn reset_tty_mode() -> Result<(), Errno> {
+    result!(unsafe { uv_tty_reset_mode() })
+}
+
 // impl
 
 impl TTYStream {
@@ -85,7 +91,7 @@ impl TTYStream {
     }
 
     pub fn reset_mode(&mut self) -> Result<(), Errno> {
-        result!(unsafe { uv_tty_reset_mode() })
+        reset_tty_mode()
     }
 
     pub fn get_winsize(&self) -> Result<(i32, i32), Errno> {
@@ -112,40 +118,6 @@ impl TTYStream {
             Ok(VTerminal::from_inner(state))
         }
     }
-
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        let handle = self.into_handle();
-        if let Some(context) = unsafe { handle.get_context::<StreamContext>() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "CheckHandle::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
-        } else {
-            None
-        }
-    }
-
-    pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
-        let mut handle = self.into_handle();
-        match unsafe { handle.get_context::<StreamContext>() } {
-            Some(context) => context.data = data,
-            None => {
-                handle.set_context(StreamContext {
-                    alloc_cb: None,
-                    close_cb: None,
-                    data,
-                    connection_cb: None,
-                    read_cb: None,
-                });
-            }
-        }
-    }
 }
 
 impl Loop {
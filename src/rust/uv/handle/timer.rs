@@ -0,0 +1,198 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    time::Duration,
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        AllocCallback, CloseCallback, Completion, Errno, IHandle, Loop, UserData, completion,
+        uv_handle_t, uv_timer_again, uv_timer_get_due_in, uv_timer_get_repeat, uv_timer_init,
+        uv_timer_set_repeat, uv_timer_start, uv_timer_stop, uv_timer_t,
+    },
+};
+
+// super
+
+impl<'a> super::IHandleContext<'a> for TimerContext<'a> {
+    fn into_handle_context(self) -> super::HandleContext<'a> {
+        super::HandleContext::from(self)
+    }
+}
+
+impl super::IHandle for TimerHandle {
+    fn into_handle(self) -> super::Handle {
+        super::Handle::from_inner(self.raw as *mut uv_handle_t)
+    }
+
+    fn drop_handle(self) {
+        let layout = Layout::new::<uv_timer_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+// type
+
+pub struct TimerCallback<'a>(pub Box<dyn FnMut(&'a TimerHandle) + 'a>);
+
+#[derive(Default)]
+#[repr(C)]
+pub struct TimerContext<'a> {
+    alloc_cb: Option<AllocCallback<'a>>,
+    close_cb: Option<CloseCallback<'a>>,
+    data: Option<UserData>,
+    timer_cb: Option<TimerCallback<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TimerHandle {
+    raw: *mut uv_timer_t,
+}
+
+// fn
+
+pub(crate) unsafe extern "C" fn uv_timer_cb(handle: *mut uv_timer_t) {
+    let handle = TimerHandle::from_inner(handle);
+    if let Some(context) = handle.into_handle().get_context::<TimerContext>() {
+        if let Some(ref mut timer_cb) = context.timer_cb {
+            timer_cb.0(&handle);
+        }
+    }
+}
+
+// impl
+
+impl TimerHandle {
+    fn new(r#loop: &Loop) -> Result<Self, Errno> {
+        let layout = Layout::new::<uv_timer_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_timer_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_handle(raw as *mut uv_handle_t);
+
+        let result = unsafe { uv_timer_init(r#loop.into_inner(), raw) };
+        if result < 0 {
+            unsafe { dealloc(raw as *mut u8, layout) };
+            return Err(Errno::from_inner(result));
+        }
+
+        Ok(Self { raw })
+    }
+
+    pub fn start<'a, TCB>(
+        &mut self,
+        timer_cb: TCB,
+        timeout: Duration,
+        repeat: Duration,
+    ) -> Result<(), Errno>
+    where
+        TCB: Into<TimerCallback<'a>>,
+    {
+        let mut handle = self.into_handle();
+        match unsafe { handle.get_context::<TimerContext>() } {
+            Some(ref mut context) => {
+                context.timer_cb = Some(timer_cb.into());
+            }
+            None => {
+                handle.set_context(TimerContext {
+                    alloc_cb: None,
+                    close_cb: None,
+                    data: None,
+                    timer_cb: Some(timer_cb.into()),
+                });
+            }
+        };
+
+        result!(unsafe {
+            uv_timer_start(
+                self.raw,
+                Some(uv_timer_cb),
+                timeout.as_millis() as u64,
+                repeat.as_millis() as u64,
+            )
+        })
+    }
+
+    pub fn stop(&mut self) -> Result<(), Errno> {
+        result!(unsafe { uv_timer_stop(self.raw) })
+    }
+
+    pub fn again(&mut self) -> Result<(), Errno> {
+        result!(unsafe { uv_timer_again(self.raw) })
+    }
+
+    pub fn set_repeat(&mut self, repeat: Duration) {
+        unsafe { uv_timer_set_repeat(self.raw, repeat.as_millis() as u64) }
+    }
+
+    pub fn get_repeat(&self) -> Duration {
+        Duration::from_millis(unsafe { uv_timer_get_repeat(self.raw) })
+    }
+
+    pub fn get_due_in(&self) -> Duration {
+        Duration::from_millis(unsafe { uv_timer_get_due_in(self.raw) })
+    }
+
+    pub fn sleep(&mut self, timeout: Duration) -> Completion<Result<(), Errno>> {
+        let (completer, completion) = completion();
+        let on_timeout = completer.clone();
+        if let Err(err) = self.start(
+            move |_: &TimerHandle| on_timeout.complete(Ok(())),
+            timeout,
+            Duration::ZERO,
+        ) {
+            completer.complete(Err(err));
+        }
+        completion
+    }
+}
+
+impl Loop {
+    pub fn new_timer(&self) -> Result<TimerHandle, Errno> {
+        return TimerHandle::new(self);
+    }
+}
+
+// trait
+
+impl<'a> From<TimerContext<'a>> for super::HandleContext<'a> {
+    fn from(value: TimerContext<'a>) -> Self {
+        Self {
+            alloc_cb: value.alloc_cb,
+            close_cb: value.close_cb,
+            data: value.data,
+        }
+    }
+}
+
+impl<'a, Fn> From<Fn> for TimerCallback<'a>
+where
+    Fn: FnMut(&TimerHandle) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for TimerCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_| ()))
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_timer_t> for TimerHandle {
+    fn from_inner(raw: *mut uv_timer_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_timer_t> for TimerHandle {
+    fn into_inner(self) -> *mut uv_timer_t {
+        self.raw
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    any::type_name,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::uv::{CloseCallback, Handle, IHandle, UserData};
+
+// type
+
+// NOTE: neither Clone nor Copy, the data is only reachable through the one TypedHandle owning it.
+// It derefs to the data, the handle is only handed out by handle() so closing goes through close
+#[derive(Debug)]
+pub struct TypedHandle<T: IHandle, D: 'static> {
+    handle: T,
+    inner: Handle,
+    data: PhantomData<D>,
+}
+
+// impl
+
+impl<T: IHandle, D: 'static> TypedHandle<T, D> {
+    pub fn new(handle: T, data: D) -> Self {
+        let mut inner = handle.into_handle();
+        inner.set_user_data(Some(UserData::new(data)));
+        Self {
+            handle,
+            inner,
+            data: PhantomData,
+        }
+    }
+
+    // NOTE: the caller makes sure no other TypedHandle wraps the same handle
+    pub unsafe fn from_handle(handle: T) -> Option<Self> {
+        let inner = handle.into_handle();
+        if !inner.user_data().is_some_and(|data| data.is::<D>()) {
+            return None;
+        }
+
+        Some(Self {
+            handle,
+            inner,
+            data: PhantomData,
+        })
+    }
+
+    pub fn handle(&self) -> T {
+        self.handle
+    }
+
+    pub fn data(&self) -> &D {
+        self.inner
+            .user_data()
+            .and_then(|data| data.get::<D>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "TypedHandle::data: [{}] no longer holds [{}]",
+                    self.handle.get_type(),
+                    type_name::<D>()
+                )
+            })
+    }
+
+    pub fn data_mut(&mut self) -> &mut D {
+        let r#type = self.handle.get_type();
+        self.inner
+            .user_data_mut()
+            .and_then(|data| data.get_mut::<D>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "TypedHandle::data_mut: [{}] no longer holds [{}]",
+                    r#type,
+                    type_name::<D>()
+                )
+            })
+    }
+
+    pub fn replace(&mut self, data: D) {
+        *self.data_mut() = data;
+    }
+
+    // NOTE: the data is dropped with the handle context once the close callback has run
+    pub fn close<'a, CCB>(mut self, close_cb: CCB)
+    where
+        CCB: Into<CloseCallback<'a>>,
+    {
+        self.inner.close(close_cb);
+    }
+}
+
+// trait
+
+impl<T: IHandle, D: 'static> Deref for TypedHandle<T, D> {
+    type Target = D;
+
+    fn deref(&self) -> &Self::Target {
+        self.data()
+    }
+}
+
+impl<T: IHandle, D: 'static> DerefMut for TypedHandle<T, D> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.data_mut()
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    error::Error,
+    ffi::{CStr, CString},
+    fmt::Display,
+    marker::PhantomData,
+    mem::{size_of, transmute_copy},
+    ops::Deref,
+    os::raw::c_void,
+    path::Path,
+    ptr::null_mut,
+};
+
+use crate::uv::{Errno, uv_dlclose, uv_dlerror, uv_dlopen, uv_dlsym, uv_lib_t};
+
+// type
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryError {
+    message: String,
+}
+
+#[derive(Debug)]
+pub struct Library {
+    raw: *mut uv_lib_t,
+}
+
+// SAFETY: uv_lib_t is only an OS handle and the last error, moving it to another thread is fine.
+// It is not Sync, get and error both go through the error message libuv keeps in the uv_lib_t
+unsafe impl Send for Library {}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Symbol<'a, T> {
+    value: T,
+    library: PhantomData<&'a Library>,
+}
+
+// impl
+
+impl LibraryError {
+    fn new<D: Display>(message: D) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Library {
+    pub fn open(path: &Path) -> Result<Self, LibraryError> {
+        let filename = CString::new(path.as_os_str().as_encoded_bytes())
+            .map_err(|_| LibraryError::new(Errno::EINVAL))?;
+
+        let layout = Layout::new::<uv_lib_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_lib_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        let library = Self { raw };
+        if unsafe { uv_dlopen(filename.as_ptr(), raw) } < 0 {
+            // dropping the library releases the error message as well
+            return Err(library.error());
+        }
+
+        Ok(library)
+    }
+
+    // NOTE: T must be the exact fn pointer (or pointer) type the symbol was exported as
+    pub unsafe fn get<T: Copy>(&self, name: &str) -> Result<Symbol<'_, T>, LibraryError> {
+        if size_of::<T>() != size_of::<*mut c_void>() {
+            return Err(LibraryError::new(format!(
+                "Library::get: [{}] is not pointer sized",
+                std::any::type_name::<T>()
+            )));
+        }
+
+        let name = CString::new(name).map_err(|_| LibraryError::new(Errno::EINVAL))?;
+        let mut ptr: *mut c_void = null_mut();
+        if uv_dlsym(self.raw, name.as_ptr(), &mut ptr) < 0 {
+            return Err(self.error());
+        }
+
+        Ok(Symbol {
+            value: transmute_copy(&ptr),
+            library: PhantomData,
+        })
+    }
+
+    pub fn close(self) {
+        drop(self)
+    }
+
+    fn error(&self) -> LibraryError {
+        LibraryError::new(unsafe { CStr::from_ptr(uv_dlerror(self.raw)) }.to_string_lossy())
+    }
+}
+
+// trait
+
+impl Display for LibraryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for LibraryError {}
+
+impl<'a, T> Deref for Symbol<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe { uv_dlclose(self.raw) };
+        let layout = Layout::new::<uv_lib_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
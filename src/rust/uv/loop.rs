@@ -1,8 +1,12 @@
 use std::{
     alloc::{Layout, alloc, dealloc},
-    any::{Any, TypeId},
-    os::raw::c_void,
+    os::raw::{c_int, c_void},
     ptr::null_mut,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
 };
 
 use chrono::{DateTime, Utc};
@@ -11,9 +15,10 @@ use crate::{
     inners::{FromInner, IntoInner},
     result,
     uv::{
-        self, Errno, uv_default_loop, uv_loop_alive, uv_loop_close, uv_loop_configure,
+        self, Errno, Handle, UserData, os, uv_backend_fd, uv_backend_timeout, uv_default_loop,
+        uv_handle_t, uv_loop_alive, uv_loop_close, uv_loop_configure, uv_loop_fork,
         uv_loop_get_data, uv_loop_init, uv_loop_option, uv_loop_set_data, uv_loop_t, uv_now,
-        uv_run, uv_run_mode, uv_stop, uv_update_time,
+        uv_replace_allocator, uv_run, uv_run_mode, uv_stop, uv_update_time, uv_walk,
     },
 };
 
@@ -22,8 +27,8 @@ use crate::{
 #[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfigurationOption {
-    BLOCK_SIGNAL,
     METRICS_IDLE_TIME,
+    USE_IO_URING_SQPOLL,
 }
 
 #[allow(non_camel_case_types)]
@@ -34,9 +39,34 @@ pub enum RunMode {
     NOWAIT,
 }
 
+pub const THREADPOOL_SIZE_MAX: usize = 1024;
+
+// set once a loop or a work request exists, after that libuv has read its process wide settings
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+// NOTE: only newer libuv releases know this option, older ones answer ENOSYS
+const UV_LOOP_USE_IO_URING_SQPOLL: uv_loop_option = 2;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Allocator {
+    pub malloc: unsafe extern "C" fn(size: usize) -> *mut c_void,
+    pub realloc: unsafe extern "C" fn(ptr: *mut c_void, size: usize) -> *mut c_void,
+    pub calloc: unsafe extern "C" fn(count: usize, size: usize) -> *mut c_void,
+    pub free: unsafe extern "C" fn(ptr: *mut c_void),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoopBuilder {
+    block_signals: Vec<i32>,
+    metrics_idle_time: bool,
+    use_io_uring_sqpoll: bool,
+    threadpool_size: Option<usize>,
+    allocator: Option<Allocator>,
+}
+
 #[repr(C)]
 pub struct LoopContext {
-    data: *mut c_void,
+    data: Option<UserData>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -48,12 +78,21 @@ pub struct Loop {
 // fn
 
 pub(crate) fn init_loop(raw: *mut uv_loop_t) {
+    mark_started();
     unsafe { uv_loop_set_data(raw, null_mut()) };
 }
 
+pub(crate) fn mark_started() {
+    STARTED.store(true, Ordering::Relaxed);
+}
+
+pub(crate) unsafe extern "C" fn uv_walk_cb(handle: *mut uv_handle_t, arg: *mut c_void) {
+    let walk_cb = &mut *(arg as *mut &mut dyn FnMut(Handle));
+    walk_cb(Handle::from_inner(handle));
+}
+
 // impl
 
-// TODO: impl walk;
 impl Loop {
     pub fn new() -> Result<Self, Errno> {
         let layout = Layout::new::<uv_loop_t>();
@@ -77,6 +116,17 @@ impl Loop {
         result!(unsafe { uv_loop_configure(self.raw, option.into_inner()) })
     }
 
+    // NOTE: UV_LOOP_BLOCK_SIGNAL takes the signal number too, so it is not a ConfigurationOption
+    pub fn block_signal(&mut self, signum: i32) -> Result<(), Errno> {
+        result!(unsafe {
+            uv_loop_configure(
+                self.raw,
+                uv::uv_loop_option_UV_LOOP_BLOCK_SIGNAL,
+                signum as c_int,
+            )
+        })
+    }
+
     pub fn close(mut self) -> Result<(), Errno> {
         let result = unsafe { uv_loop_close(self.raw) };
         if result < 0 {
@@ -92,10 +142,32 @@ impl Loop {
         Ok(())
     }
 
+    // NOTE: call in the child after fork(2), before running the loop again
+    pub fn fork(&mut self) -> Result<(), Errno> {
+        result!(unsafe { uv_loop_fork(self.raw) })
+    }
+
     pub fn run(&mut self, mode: RunMode) -> Result<(), Errno> {
         result!(unsafe { uv_run(self.raw, mode.into_inner()) })
     }
 
+    pub fn run_nowait_after_ready(&mut self) -> Result<bool, Errno> {
+        self.update_time();
+        self.run(RunMode::NOWAIT)?;
+        Ok(self.alive())
+    }
+
+    pub fn backend_fd(&self) -> i32 {
+        unsafe { uv_backend_fd(self.raw) }
+    }
+
+    pub fn backend_timeout(&self) -> Option<Duration> {
+        match unsafe { uv_backend_timeout(self.raw) } {
+            timeout if timeout < 0 => None,
+            timeout => Some(Duration::from_millis(timeout as u64)),
+        }
+    }
+
     pub fn alive(&self) -> bool {
         unsafe { uv_loop_alive(self.raw) != 0 }
     }
@@ -104,6 +176,20 @@ impl Loop {
         unsafe { uv_stop(self.raw) }
     }
 
+    pub fn walk<WCB>(&self, mut walk_cb: WCB)
+    where
+        WCB: FnMut(Handle),
+    {
+        let mut walk_cb: &mut dyn FnMut(Handle) = &mut walk_cb;
+        unsafe {
+            uv_walk(
+                self.raw,
+                Some(uv_walk_cb),
+                &mut walk_cb as *mut &mut dyn FnMut(Handle) as *mut c_void,
+            )
+        };
+    }
+
     pub fn now(&self) -> Option<DateTime<Utc>> {
         DateTime::from_timestamp_millis(unsafe { uv_now(self.raw) } as i64)
     }
@@ -112,24 +198,16 @@ impl Loop {
         unsafe { uv_update_time(self.raw) }
     }
 
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        if let Some(context) = unsafe { self.get_context() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "Loop::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
-        } else {
-            None
-        }
+    pub fn get_data<D: 'static>(&self) -> Option<&D> {
+        unsafe { self.get_context() }.and_then(|context| context.data.as_ref()?.get::<D>())
+    }
+
+    pub fn get_data_mut<D: 'static>(&mut self) -> Option<&mut D> {
+        unsafe { self.get_context() }.and_then(|context| context.data.as_mut()?.get_mut::<D>())
     }
 
     pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
+        let data = Some(UserData::new(data));
         match unsafe { self.get_context() } {
             Some(context) => context.data = data,
             None => {
@@ -153,10 +231,8 @@ impl Loop {
 
     pub(crate) fn drop_context(&mut self) {
         if let Some(context) = unsafe { self.get_context() } {
-            if !context.data.is_null() {
-                drop(unsafe { Box::from_raw(context.data) })
-            }
-            drop(unsafe { Box::from_raw(context) })
+            drop(unsafe { Box::from_raw(context) });
+            unsafe { uv_loop_set_data(self.raw, null_mut()) };
         }
     }
 }
@@ -165,6 +241,7 @@ impl Loop {
 
 impl Default for Loop {
     fn default() -> Self {
+        mark_started();
         let raw = unsafe { uv_default_loop() };
         if raw.is_null() {
             panic!("{}", Errno::ENOMEM);
@@ -176,23 +253,123 @@ impl Default for Loop {
     }
 }
 
+impl LoopBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn block_signal(mut self, signum: i32) -> Self {
+        self.block_signals.push(signum);
+        self
+    }
+
+    pub fn metrics_idle_time(mut self, enable: bool) -> Self {
+        self.metrics_idle_time = enable;
+        self
+    }
+
+    pub fn use_io_uring_sqpoll(mut self, enable: bool) -> Self {
+        self.use_io_uring_sqpoll = enable;
+        self
+    }
+
+    // NOTE: must be set before any loop or work request exists, build answers EBUSY otherwise
+    pub fn threadpool_size(mut self, size: usize) -> Self {
+        self.threadpool_size = Some(size);
+        self
+    }
+
+    // NOTE: same as threadpool_size, libuv allocates with the default allocator from the start
+    pub fn allocator(mut self, allocator: Allocator) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+
+    pub fn build(self) -> Result<Loop, Errno> {
+        self.init_process()?;
+        let mut r#loop = Loop::new()?;
+        if let Err(err) = self.configure(&mut r#loop) {
+            r#loop.close()?;
+            return Err(err);
+        }
+        Ok(r#loop)
+    }
+
+    pub fn build_default(self) -> Result<Loop, Errno> {
+        self.init_process()?;
+        let mut r#loop = Loop::default();
+        self.configure(&mut r#loop)?;
+        Ok(r#loop)
+    }
+
+    pub fn configure(&self, r#loop: &mut Loop) -> Result<(), Errno> {
+        for signum in &self.block_signals {
+            r#loop.block_signal(*signum)?;
+        }
+        if self.metrics_idle_time {
+            r#loop.configure(ConfigurationOption::METRICS_IDLE_TIME)?;
+        }
+        if self.use_io_uring_sqpoll {
+            r#loop.configure(ConfigurationOption::USE_IO_URING_SQPOLL)?;
+        }
+        Ok(())
+    }
+
+    // NOTE: the allocator and the thread pool are process wide and fixed once libuv first uses them
+    fn init_process(&self) -> Result<(), Errno> {
+        static THREADPOOL_SIZE: OnceLock<usize> = OnceLock::new();
+        static ALLOCATOR: OnceLock<()> = OnceLock::new();
+
+        if let Some(size) = self.threadpool_size {
+            if size == 0 || size > THREADPOOL_SIZE_MAX {
+                return Err(Errno::EINVAL);
+            }
+            match THREADPOOL_SIZE.get() {
+                Some(current) if *current == size => {}
+                Some(_) => return Err(Errno::EBUSY),
+                None if STARTED.load(Ordering::Relaxed) => return Err(Errno::EBUSY),
+                None => {
+                    os::setenv("UV_THREADPOOL_SIZE", &size.to_string())?;
+                    let _ = THREADPOOL_SIZE.set(size);
+                }
+            }
+        }
+
+        if let Some(allocator) = self.allocator {
+            if STARTED.load(Ordering::Relaxed) || ALLOCATOR.set(()).is_err() {
+                return Err(Errno::EBUSY);
+            }
+            result!(unsafe {
+                uv_replace_allocator(
+                    Some(allocator.malloc),
+                    Some(allocator.realloc),
+                    Some(allocator.calloc),
+                    Some(allocator.free),
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
 // inner
 
 impl FromInner<uv_loop_option> for ConfigurationOption {
     fn from_inner(value: uv_loop_option) -> Self {
         match value {
-            uv::uv_loop_option_UV_LOOP_BLOCK_SIGNAL => ConfigurationOption::BLOCK_SIGNAL,
             uv::uv_loop_option_UV_METRICS_IDLE_TIME => ConfigurationOption::METRICS_IDLE_TIME,
+            UV_LOOP_USE_IO_URING_SQPOLL => ConfigurationOption::USE_IO_URING_SQPOLL,
             _ => unreachable!(),
         }
     }
 }
 
 impl IntoInner<uv_loop_option> for ConfigurationOption {
-    fn into_inner(self) -> uv_run_mode {
+    fn into_inner(self) -> uv_loop_option {
         match self {
-            ConfigurationOption::BLOCK_SIGNAL => uv::uv_loop_option_UV_LOOP_BLOCK_SIGNAL,
             ConfigurationOption::METRICS_IDLE_TIME => uv::uv_loop_option_UV_METRICS_IDLE_TIME,
+            ConfigurationOption::USE_IO_URING_SQPOLL => UV_LOOP_USE_IO_URING_SQPOLL,
         }
     }
 }
@@ -16,9 +16,32 @@ pub(crate) use handle::*;
 pub(crate) mod request;
 pub(crate) use request::*;
 
+pub(crate) mod data;
+pub(crate) use data::*;
+
 pub(crate) mod buf;
 pub(crate) use buf::*;
 
+pub(crate) mod net;
+pub(crate) use net::*;
+
+pub(crate) mod future;
+pub(crate) use future::*;
+
+pub(crate) mod executor;
+pub(crate) use executor::*;
+
+pub(crate) mod thread;
+pub(crate) use thread::*;
+
+pub(crate) mod embed;
+pub(crate) use embed::*;
+
+pub(crate) mod library;
+pub(crate) use library::*;
+
+pub(crate) mod os;
+
 pub(crate) mod util;
 pub(crate) use util::*;
 
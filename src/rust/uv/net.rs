@@ -0,0 +1,95 @@
+use std::{
+    mem::zeroed,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    ptr::copy_nonoverlapping,
+};
+
+use crate::uv::{
+    AF_INET, AF_INET6, sa_family_t, sockaddr, sockaddr_in, sockaddr_in6, sockaddr_storage,
+};
+
+// type
+
+pub const SOCK_STREAM: i32 = 1;
+pub const SOCK_DGRAM: i32 = 2;
+
+pub const IPPROTO_TCP: i32 = 6;
+pub const IPPROTO_UDP: i32 = 17;
+
+pub const AI_PASSIVE: i32 = 1;
+pub const AI_CANONNAME: i32 = 2;
+pub const AI_NUMERICHOST: i32 = 4;
+pub const AI_V4MAPPED: i32 = 8;
+pub const AI_ALL: i32 = 16;
+pub const AI_ADDRCONFIG: i32 = 32;
+pub const AI_NUMERICSERV: i32 = 1024;
+
+pub const NI_NUMERICHOST: i32 = 1;
+pub const NI_NUMERICSERV: i32 = 2;
+pub const NI_NOFQDN: i32 = 4;
+pub const NI_NAMEREQD: i32 = 8;
+pub const NI_DGRAM: i32 = 16;
+
+// fn
+
+pub(crate) fn to_sockaddr(addr: &SocketAddr) -> sockaddr_storage {
+    let mut storage: sockaddr_storage = unsafe { zeroed() };
+    match addr {
+        SocketAddr::V4(addr) => {
+            let raw = &mut storage as *mut sockaddr_storage as *mut sockaddr_in;
+            unsafe {
+                (*raw).sin_family = AF_INET as sa_family_t;
+                (*raw).sin_port = addr.port().to_be();
+                (*raw).sin_addr.s_addr = u32::from_ne_bytes(addr.ip().octets());
+            }
+        }
+        SocketAddr::V6(addr) => {
+            let raw = &mut storage as *mut sockaddr_storage as *mut sockaddr_in6;
+            unsafe {
+                (*raw).sin6_family = AF_INET6 as sa_family_t;
+                (*raw).sin6_port = addr.port().to_be();
+                (*raw).sin6_flowinfo = addr.flowinfo().to_be();
+                (*raw).sin6_scope_id = addr.scope_id();
+                copy_nonoverlapping(
+                    addr.ip().octets().as_ptr(),
+                    &mut (*raw).sin6_addr as *mut _ as *mut u8,
+                    16,
+                );
+            }
+        }
+    }
+    storage
+}
+
+pub(crate) unsafe fn from_sockaddr(raw: *const sockaddr) -> Option<SocketAddr> {
+    if raw.is_null() {
+        return None;
+    }
+
+    match (*raw).sa_family as u32 {
+        AF_INET => {
+            let raw = raw as *const sockaddr_in;
+            let ip = Ipv4Addr::from((*raw).sin_addr.s_addr.to_ne_bytes());
+            Some(SocketAddr::V4(SocketAddrV4::new(
+                ip,
+                u16::from_be((*raw).sin_port),
+            )))
+        }
+        AF_INET6 => {
+            let raw = raw as *const sockaddr_in6;
+            let mut octets = [0u8; 16];
+            copy_nonoverlapping(
+                &(*raw).sin6_addr as *const _ as *const u8,
+                octets.as_mut_ptr(),
+                16,
+            );
+            Some(SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(octets),
+                u16::from_be((*raw).sin6_port),
+                u32::from_be((*raw).sin6_flowinfo),
+                (*raw).sin6_scope_id,
+            )))
+        }
+        _ => None,
+    }
+}
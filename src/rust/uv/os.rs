@@ -0,0 +1,377 @@
+use std::{
+    ffi::{CStr, CString},
+    mem::zeroed,
+    net::IpAddr,
+    os::raw::{c_char, c_int},
+    ptr::null_mut,
+    slice::from_raw_parts,
+    time::Duration,
+};
+
+use crate::{
+    inners::FromInner,
+    result,
+    uv::{
+        Errno, from_sockaddr, sockaddr, uv_available_parallelism, uv_cpu_info, uv_cpu_info_t,
+        uv_env_item_t, uv_free_cpu_info, uv_free_interface_addresses, uv_get_available_memory,
+        uv_get_constrained_memory, uv_get_free_memory, uv_get_total_memory, uv_getrusage,
+        uv_interface_address_t, uv_interface_addresses, uv_loadavg, uv_os_environ,
+        uv_os_free_environ, uv_os_free_passwd, uv_os_get_passwd, uv_os_getenv, uv_os_gethostname,
+        uv_os_getpid, uv_os_getppid, uv_os_getpriority, uv_os_homedir, uv_os_setenv,
+        uv_os_setpriority, uv_os_tmpdir, uv_os_uname, uv_os_unsetenv, uv_passwd_t,
+        uv_resident_set_memory, uv_rusage_t, uv_timeval_t, uv_uptime, uv_utsname_t,
+    },
+};
+
+// type
+
+pub const PRIORITY_LOW: i32 = 19;
+pub const PRIORITY_BELOW_NORMAL: i32 = 10;
+pub const PRIORITY_NORMAL: i32 = 0;
+pub const PRIORITY_ABOVE_NORMAL: i32 = -7;
+pub const PRIORITY_HIGH: i32 = -14;
+pub const PRIORITY_HIGHEST: i32 = -20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Passwd {
+    pub username: String,
+    pub uid: u64,
+    pub gid: u64,
+    pub shell: Option<String>,
+    pub homedir: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uname {
+    pub sysname: String,
+    pub release: String,
+    pub version: String,
+    pub machine: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub sys: u64,
+    pub idle: u64,
+    pub irq: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuInfo {
+    pub model: String,
+    pub speed: i32,
+    pub times: CpuTimes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceAddress {
+    pub name: String,
+    pub phys_addr: [u8; 6],
+    pub is_internal: bool,
+    pub address: Option<IpAddr>,
+    pub netmask: Option<IpAddr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub utime: Duration,
+    pub stime: Duration,
+    pub maxrss: u64,
+    pub ixrss: u64,
+    pub idrss: u64,
+    pub isrss: u64,
+    pub minflt: u64,
+    pub majflt: u64,
+    pub nswap: u64,
+    pub inblock: u64,
+    pub oublock: u64,
+    pub msgsnd: u64,
+    pub msgrcv: u64,
+    pub nsignals: u64,
+    pub nvcsw: u64,
+    pub nivcsw: u64,
+}
+
+// fn
+
+unsafe fn to_string(raw: *const c_char) -> String {
+    CStr::from_ptr(raw).to_string_lossy().into_owned()
+}
+
+fn to_duration(tv: uv_timeval_t) -> Duration {
+    Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+}
+
+// NOTE: libuv reports the required size through `size` when the buffer is too small and the
+// length without the terminator on success, abstract pipe names even start with a nul
+pub(crate) fn read_string<F>(mut read: F) -> Result<String, Errno>
+where
+    F: FnMut(*mut c_char, *mut usize) -> c_int,
+{
+    let mut buffer = vec![0u8; 256];
+    loop {
+        let mut size = buffer.len();
+        let result = read(buffer.as_mut_ptr() as *mut c_char, &mut size);
+        match result {
+            0 => {
+                buffer.truncate(size);
+                return Ok(String::from_utf8_lossy(&buffer).into_owned());
+            }
+            result if Errno::from_inner(result) == Errno::ENOBUFS => {
+                buffer.resize(size + 1, 0);
+            }
+            result => return Err(Errno::from_inner(result)),
+        }
+    }
+}
+
+pub fn hostname() -> Result<String, Errno> {
+    read_string(|buffer, size| unsafe { uv_os_gethostname(buffer, size) })
+}
+
+pub fn homedir() -> Result<String, Errno> {
+    read_string(|buffer, size| unsafe { uv_os_homedir(buffer, size) })
+}
+
+pub fn tmpdir() -> Result<String, Errno> {
+    read_string(|buffer, size| unsafe { uv_os_tmpdir(buffer, size) })
+}
+
+pub fn uname() -> Result<Uname, Errno> {
+    let mut buffer: uv_utsname_t = unsafe { zeroed() };
+    let result = unsafe { uv_os_uname(&mut buffer) };
+    if result < 0 {
+        return Err(Errno::from_inner(result));
+    }
+
+    Ok(unsafe {
+        Uname {
+            sysname: to_string(buffer.sysname.as_ptr()),
+            release: to_string(buffer.release.as_ptr()),
+            version: to_string(buffer.version.as_ptr()),
+            machine: to_string(buffer.machine.as_ptr()),
+        }
+    })
+}
+
+pub fn passwd() -> Result<Passwd, Errno> {
+    let mut pwd: uv_passwd_t = unsafe { zeroed() };
+    let result = unsafe { uv_os_get_passwd(&mut pwd) };
+    if result < 0 {
+        return Err(Errno::from_inner(result));
+    }
+
+    let passwd = unsafe {
+        Passwd {
+            username: to_string(pwd.username),
+            uid: pwd.uid as u64,
+            gid: pwd.gid as u64,
+            shell: (!pwd.shell.is_null()).then(|| to_string(pwd.shell)),
+            homedir: to_string(pwd.homedir),
+        }
+    };
+    unsafe { uv_os_free_passwd(&mut pwd) };
+
+    Ok(passwd)
+}
+
+pub fn environ() -> Result<Vec<(String, String)>, Errno> {
+    let mut items: *mut uv_env_item_t = null_mut();
+    let mut count: c_int = 0;
+    let result = unsafe { uv_os_environ(&mut items, &mut count) };
+    if result < 0 {
+        return Err(Errno::from_inner(result));
+    }
+    // NOTE: libuv may leave the pointer null when there is nothing to list
+    if count == 0 {
+        unsafe { uv_os_free_environ(items, count) };
+        return Ok(Vec::new());
+    }
+
+    let environ = unsafe { from_raw_parts(items, count as usize) }
+        .iter()
+        .map(|item| unsafe { (to_string(item.name), to_string(item.value)) })
+        .collect();
+    unsafe { uv_os_free_environ(items, count) };
+
+    Ok(environ)
+}
+
+pub fn getenv(name: &str) -> Result<Option<String>, Errno> {
+    let name = CString::new(name).map_err(|_| Errno::EINVAL)?;
+    match read_string(|buffer, size| unsafe { uv_os_getenv(name.as_ptr(), buffer, size) }) {
+        Ok(value) => Ok(Some(value)),
+        Err(Errno::ENOENT) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn setenv(name: &str, value: &str) -> Result<(), Errno> {
+    let name = CString::new(name).map_err(|_| Errno::EINVAL)?;
+    let value = CString::new(value).map_err(|_| Errno::EINVAL)?;
+    result!(unsafe { uv_os_setenv(name.as_ptr(), value.as_ptr()) })
+}
+
+pub fn unsetenv(name: &str) -> Result<(), Errno> {
+    let name = CString::new(name).map_err(|_| Errno::EINVAL)?;
+    result!(unsafe { uv_os_unsetenv(name.as_ptr()) })
+}
+
+pub fn getpid() -> i32 {
+    unsafe { uv_os_getpid() }
+}
+
+pub fn getppid() -> i32 {
+    unsafe { uv_os_getppid() }
+}
+
+pub fn getpriority(pid: i32) -> Result<i32, Errno> {
+    let mut priority: c_int = 0;
+    let result = unsafe { uv_os_getpriority(pid, &mut priority) };
+    if result < 0 {
+        Err(Errno::from_inner(result))
+    } else {
+        Ok(priority)
+    }
+}
+
+pub fn setpriority(pid: i32, priority: i32) -> Result<(), Errno> {
+    result!(unsafe { uv_os_setpriority(pid, priority) })
+}
+
+pub fn available_parallelism() -> u32 {
+    unsafe { uv_available_parallelism() }
+}
+
+pub fn cpu_info() -> Result<Vec<CpuInfo>, Errno> {
+    let mut infos: *mut uv_cpu_info_t = null_mut();
+    let mut count: c_int = 0;
+    let result = unsafe { uv_cpu_info(&mut infos, &mut count) };
+    if result < 0 {
+        return Err(Errno::from_inner(result));
+    }
+    if count == 0 {
+        unsafe { uv_free_cpu_info(infos, count) };
+        return Ok(Vec::new());
+    }
+
+    let cpu_info = unsafe { from_raw_parts(infos, count as usize) }
+        .iter()
+        .map(|info| CpuInfo {
+            model: unsafe { to_string(info.model) },
+            speed: info.speed,
+            times: CpuTimes {
+                user: info.cpu_times.user,
+                nice: info.cpu_times.nice,
+                sys: info.cpu_times.sys,
+                idle: info.cpu_times.idle,
+                irq: info.cpu_times.irq,
+            },
+        })
+        .collect();
+    unsafe { uv_free_cpu_info(infos, count) };
+
+    Ok(cpu_info)
+}
+
+pub fn loadavg() -> [f64; 3] {
+    let mut avg = [0f64; 3];
+    unsafe { uv_loadavg(avg.as_mut_ptr()) };
+    avg
+}
+
+pub fn free_memory() -> u64 {
+    unsafe { uv_get_free_memory() }
+}
+
+pub fn total_memory() -> u64 {
+    unsafe { uv_get_total_memory() }
+}
+
+pub fn constrained_memory() -> u64 {
+    unsafe { uv_get_constrained_memory() }
+}
+
+pub fn available_memory() -> u64 {
+    unsafe { uv_get_available_memory() }
+}
+
+pub fn resident_set_memory() -> Result<usize, Errno> {
+    let mut rss: usize = 0;
+    let result = unsafe { uv_resident_set_memory(&mut rss) };
+    if result < 0 {
+        Err(Errno::from_inner(result))
+    } else {
+        Ok(rss)
+    }
+}
+
+pub fn uptime() -> Result<Duration, Errno> {
+    let mut uptime: f64 = 0.0;
+    let result = unsafe { uv_uptime(&mut uptime) };
+    if result < 0 {
+        Err(Errno::from_inner(result))
+    } else {
+        Ok(Duration::from_secs_f64(uptime))
+    }
+}
+
+pub fn getrusage() -> Result<ResourceUsage, Errno> {
+    let mut rusage: uv_rusage_t = unsafe { zeroed() };
+    let result = unsafe { uv_getrusage(&mut rusage) };
+    if result < 0 {
+        return Err(Errno::from_inner(result));
+    }
+
+    Ok(ResourceUsage {
+        utime: to_duration(rusage.ru_utime),
+        stime: to_duration(rusage.ru_stime),
+        maxrss: rusage.ru_maxrss,
+        ixrss: rusage.ru_ixrss,
+        idrss: rusage.ru_idrss,
+        isrss: rusage.ru_isrss,
+        minflt: rusage.ru_minflt,
+        majflt: rusage.ru_majflt,
+        nswap: rusage.ru_nswap,
+        inblock: rusage.ru_inblock,
+        oublock: rusage.ru_oublock,
+        msgsnd: rusage.ru_msgsnd,
+        msgrcv: rusage.ru_msgrcv,
+        nsignals: rusage.ru_nsignals,
+        nvcsw: rusage.ru_nvcsw,
+        nivcsw: rusage.ru_nivcsw,
+    })
+}
+
+pub fn interface_addresses() -> Result<Vec<InterfaceAddress>, Errno> {
+    let mut addresses: *mut uv_interface_address_t = null_mut();
+    let mut count: c_int = 0;
+    let result = unsafe { uv_interface_addresses(&mut addresses, &mut count) };
+    if result < 0 {
+        return Err(Errno::from_inner(result));
+    }
+    if count == 0 {
+        unsafe { uv_free_interface_addresses(addresses, count) };
+        return Ok(Vec::new());
+    }
+
+    let interface_addresses = unsafe { from_raw_parts(addresses, count as usize) }
+        .iter()
+        .map(|address| unsafe {
+            InterfaceAddress {
+                name: to_string(address.name),
+                phys_addr: address.phys_addr.map(|byte| byte as u8),
+                is_internal: address.is_internal != 0,
+                address: from_sockaddr(&address.address as *const _ as *const sockaddr)
+                    .map(|addr| addr.ip()),
+                netmask: from_sockaddr(&address.netmask as *const _ as *const sockaddr)
+                    .map(|addr| addr.ip()),
+            }
+        })
+        .collect();
+    unsafe { uv_free_interface_addresses(addresses, count) };
+
+    Ok(interface_addresses)
+}
@@ -0,0 +1,116 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    os::raw::c_int,
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    uv::{Errno, IRequest, UserData, uv_connect_t, uv_req_t},
+};
+
+// super
+
+impl<'a> super::IRequestContext for ConnectContext<'a> {
+    fn into_request_context(self) -> super::RequestContext {
+        super::RequestContext::from(self)
+    }
+}
+
+impl<'a> super::IRequest for ConnectRequest {
+    fn into_request(self) -> super::Request {
+        super::Request::from_inner(self.raw as *mut uv_req_t)
+    }
+
+    fn drop_request(self) {
+        let layout = Layout::new::<uv_connect_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+// type
+
+pub struct ConnectCallback<'a>(pub Box<dyn FnMut(ConnectRequest, Result<(), Errno>) + 'a>);
+
+#[derive(Default)]
+#[repr(C)]
+pub struct ConnectContext<'a> {
+    pub(crate) data: Option<UserData>,
+    pub(crate) connect_cb: Option<ConnectCallback<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectRequest {
+    raw: *mut uv_connect_t,
+}
+
+// fn
+
+pub(crate) unsafe extern "C" fn uv_connect_cb(req: *mut uv_connect_t, status: c_int) {
+    let connect = ConnectRequest::from_inner(req);
+    if let Some(context) = connect.into_request().get_context::<ConnectContext>() {
+        let status = if status < 0 {
+            Err(Errno::from_inner(status))
+        } else {
+            Ok(())
+        };
+
+        if let Some(ref mut connect_cb) = context.connect_cb {
+            connect_cb.0(connect, status);
+        }
+    }
+    connect.into_request().drop_context();
+    connect.drop_request();
+}
+
+// impl
+
+impl ConnectRequest {
+    pub fn new() -> Self {
+        let layout = Layout::new::<uv_connect_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_connect_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_request(raw as *mut uv_req_t, super::RequestType::CONNECT);
+
+        Self { raw }
+    }
+}
+
+// trait
+
+impl<'a> From<ConnectContext<'a>> for super::RequestContext {
+    fn from(value: ConnectContext<'a>) -> Self {
+        Self { data: value.data }
+    }
+}
+
+impl<'a, Fn> From<Fn> for ConnectCallback<'a>
+where
+    Fn: FnMut(ConnectRequest, Result<(), Errno>) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for ConnectCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_connect_t> for ConnectRequest {
+    fn from_inner(raw: *mut uv_connect_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_connect_t> for ConnectRequest {
+    fn into_inner(self) -> *mut uv_connect_t {
+        self.raw
+    }
+}
@@ -1,17 +1,20 @@
 use std::{
     alloc::{Layout, alloc, dealloc},
+    cell::RefCell,
+    collections::VecDeque,
     ffi::CString,
-    os::raw::c_void,
+    io::{self, Read, Write},
     path::Path,
-    ptr::null_mut,
+    rc::Rc,
 };
 
 use crate::{
     inners::{FromInner, IntoInner},
     result,
     uv::{
-        self, Buf, Errno, IRequest, Loop, uv_buf_t, uv_fs_close, uv_fs_get_result, uv_fs_open,
-        uv_fs_read, uv_fs_req_cleanup, uv_fs_t, uv_fs_type, uv_fs_write, uv_req_t,
+        self, Buf, Completion, Errno, IRequest, Loop, UserData, completion, dealloc_base, uv_buf_t,
+        uv_errno_t, uv_fs_close, uv_fs_get_result, uv_fs_open, uv_fs_read, uv_fs_req_cleanup,
+        uv_fs_t, uv_fs_type, uv_fs_write, uv_req_t,
     },
 };
 
@@ -113,9 +116,10 @@ pub struct OpenOptionSet(u32);
 
 pub struct FileSystemCallback<'a>(pub Box<dyn FnMut(FileSystemRequest) + 'a>);
 
+#[derive(Default)]
 #[repr(C)]
 pub struct FileSystemContext<'a> {
-    data: *mut c_void,
+    data: Option<UserData>,
     fs_cb: Option<FileSystemCallback<'a>>,
 }
 
@@ -124,6 +128,27 @@ pub struct FileSystemRequest {
     raw: *mut uv_fs_t,
 }
 
+#[derive(Debug)]
+struct FileState {
+    buffer: VecDeque<u8>,
+    reading: bool,
+    eof: bool,
+    read_offset: i64,
+    write_offset: i64,
+    pending: usize,
+    error: Option<Errno>,
+    closing: bool,
+}
+
+#[derive(Debug)]
+pub struct File {
+    r#loop: Loop,
+    file: i32,
+    state: Rc<RefCell<FileState>>,
+}
+
+const FILE_READ_SIZE: usize = 64 * 1024;
+
 // fn
 
 pub(crate) unsafe extern "C" fn uv_fs_cb(req: *mut uv_fs_t) {
@@ -139,6 +164,14 @@ pub(crate) unsafe extern "C" fn uv_fs_cb(req: *mut uv_fs_t) {
     fs.drop_request();
 }
 
+fn fs_status(result: isize) -> Result<isize, Errno> {
+    if result < 0 {
+        Err(Errno::from_inner(result as uv_errno_t))
+    } else {
+        Ok(result)
+    }
+}
+
 // impl
 
 impl OpenOptionSet {
@@ -169,7 +202,7 @@ impl FileSystemRequest {
             panic!("{}", Errno::ENOMEM);
         }
 
-        super::init_request(raw as *mut uv_req_t);
+        super::init_request(raw as *mut uv_req_t, super::RequestType::FS);
 
         Self { raw }
     }
@@ -183,6 +216,118 @@ impl FileSystemRequest {
     }
 }
 
+// NOTE: reads and writes run on the threadpool, File buffers them like StreamReader and
+// StreamWriter so the loop thread never waits on the disk
+impl File {
+    pub fn open(
+        r#loop: &Loop,
+        path: &Path,
+        flags: OpenOptionSet,
+        mode: OpenMode,
+    ) -> Result<Self, Errno> {
+        let file = r#loop.fs_open_sync(FileSystemRequest::new(), path, flags, mode)?;
+        Ok(Self {
+            r#loop: *r#loop,
+            file,
+            state: Rc::new(RefCell::new(FileState {
+                buffer: VecDeque::new(),
+                reading: false,
+                eof: false,
+                read_offset: 0,
+                write_offset: 0,
+                pending: 0,
+                error: None,
+                closing: false,
+            })),
+        })
+    }
+
+    pub fn fd(&self) -> i32 {
+        self.file
+    }
+
+    pub fn available(&self) -> usize {
+        self.state.borrow().buffer.len()
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.state.borrow().eof
+    }
+
+    pub fn pending(&self) -> usize {
+        self.state.borrow().pending
+    }
+
+    // NOTE: with reads or writes still in flight this answers EBUSY, dropping the file then closes
+    // the fd once they finish
+    pub fn close(mut self) -> Result<(), Errno> {
+        if !self.state.borrow().is_idle() {
+            return Err(Errno::EBUSY);
+        }
+
+        let file = std::mem::replace(&mut self.file, -1);
+        self.r#loop.fs_close_sync(FileSystemRequest::new(), file)
+    }
+
+    fn read_more(&self) -> Result<(), Errno> {
+        let buf = Buf::new_with_len(FILE_READ_SIZE);
+        let offset = self.state.borrow().read_offset;
+        let state = self.state.clone();
+        let r#loop = self.r#loop;
+        let file = self.file;
+        let result = self.r#loop.fs_read(
+            FileSystemRequest::new(),
+            self.file,
+            &[buf],
+            offset,
+            move |req: FileSystemRequest| {
+                let mut state = state.borrow_mut();
+                match fs_status(req.result()) {
+                    Ok(0) => state.eof = true,
+                    Ok(nread) => {
+                        state.buffer.extend(&buf.as_bytes()[..nread as usize]);
+                        state.read_offset += nread as i64;
+                    }
+                    Err(err) => state.error = Some(err),
+                }
+                state.reading = false;
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+                state.close_if_idle(r#loop, file);
+            },
+        );
+
+        match result {
+            Ok(()) => {
+                self.state.borrow_mut().reading = true;
+                Ok(())
+            }
+            Err(err) => {
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+                Err(err)
+            }
+        }
+    }
+
+    fn take_error(&self) -> io::Result<()> {
+        match self.state.borrow_mut().error.take() {
+            Some(err) => Err(io::Error::from(err)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FileState {
+    fn is_idle(&self) -> bool {
+        !self.reading && self.pending == 0
+    }
+
+    fn close_if_idle(&self, r#loop: Loop, file: i32) {
+        if self.closing && self.is_idle() {
+            let _ = r#loop.fs_close(FileSystemRequest::new(), file, ());
+        }
+    }
+}
+
 impl Loop {
     pub fn fs_close<'a, FSCB>(
         &self,
@@ -200,7 +345,7 @@ impl Loop {
             }
             None => {
                 request.set_context(FileSystemContext {
-                    data: null_mut(),
+                    data: None,
                     fs_cb: Some(fs_cb.into()),
                 });
             }
@@ -210,7 +355,11 @@ impl Loop {
     }
 
     pub fn fs_close_sync(&self, req: FileSystemRequest, file: i32) -> Result<(), Errno> {
-        result!(unsafe { uv_fs_close(self.into_inner(), req.into_inner(), file, None) })
+        let result = unsafe { uv_fs_close(self.into_inner(), req.into_inner(), file, None) };
+        req.cleanup();
+        req.into_request().drop_context();
+        req.drop_request();
+        result!(result)
     }
 
     pub fn fs_open<'a, FSCB>(
@@ -231,7 +380,7 @@ impl Loop {
             }
             None => {
                 request.set_context(FileSystemContext {
-                    data: null_mut(),
+                    data: None,
                     fs_cb: Some(fs_cb.into()),
                 });
             }
@@ -271,10 +420,13 @@ impl Loop {
                         path.as_ptr() as *const i8,
                         flags.0 as i32,
                         mode.into_inner() as i32,
-                        Some(uv_fs_cb),
+                        None,
                     )
                 };
 
+                req.cleanup();
+                req.into_request().drop_context();
+                req.drop_request();
                 if result < 0 {
                     Err(Errno::from_inner(result))
                 } else {
@@ -303,7 +455,7 @@ impl Loop {
             }
             None => {
                 request.set_context(FileSystemContext {
-                    data: null_mut(),
+                    data: None,
                     fs_cb: Some(fs_cb.into()),
                 });
             }
@@ -375,7 +527,7 @@ impl Loop {
             }
             None => {
                 request.set_context(FileSystemContext {
-                    data: null_mut(),
+                    data: None,
                     fs_cb: Some(fs_cb.into()),
                 });
             }
@@ -411,7 +563,7 @@ impl Loop {
                 bufs,
                 nbufs as u32,
                 offset,
-                Some(uv_fs_cb),
+                None,
             )
         };
 
@@ -425,6 +577,96 @@ impl Loop {
             Ok(ret)
         }
     }
+
+    pub fn fs_close_async(&self, file: i32) -> Completion<Result<(), Errno>> {
+        let (completer, completion) = completion();
+        let on_close = completer.clone();
+        if let Err(err) = self.fs_close(
+            FileSystemRequest::new(),
+            file,
+            move |req: FileSystemRequest| {
+                on_close.complete(fs_status(req.result()).map(|_| ()));
+            },
+        ) {
+            completer.complete(Err(err));
+        }
+        completion
+    }
+
+    pub fn fs_open_async(
+        &self,
+        path: &Path,
+        flags: OpenOptionSet,
+        mode: OpenMode,
+    ) -> Completion<Result<i32, Errno>> {
+        let (completer, completion) = completion();
+        let on_open = completer.clone();
+        if let Err(err) = self.fs_open(
+            FileSystemRequest::new(),
+            path,
+            flags,
+            mode,
+            move |req: FileSystemRequest| {
+                on_open.complete(fs_status(req.result()).map(|file| file as i32));
+            },
+        ) {
+            completer.complete(Err(err));
+        }
+        completion
+    }
+
+    pub fn fs_read_async(
+        &self,
+        file: i32,
+        len: usize,
+        offset: i64,
+    ) -> Completion<Result<Vec<u8>, Errno>> {
+        let (completer, completion) = completion();
+        let on_read = completer.clone();
+        let buf = Buf::new_with_len(len);
+        if let Err(err) = self.fs_read(
+            FileSystemRequest::new(),
+            file,
+            &[buf],
+            offset,
+            move |req: FileSystemRequest| {
+                on_read.complete(
+                    fs_status(req.result()).map(|nread| buf.as_bytes()[..nread as usize].to_vec()),
+                );
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+            },
+        ) {
+            unsafe { dealloc_base(buf.base(), buf.len()) };
+            completer.complete(Err(err));
+        }
+        completion
+    }
+
+    pub fn fs_write_async(
+        &self,
+        file: i32,
+        data: &[u8],
+        offset: i64,
+    ) -> Completion<Result<usize, Errno>> {
+        let (completer, completion) = completion();
+        let on_write = completer.clone();
+        let buf = Buf::new_with_len(data.len());
+        buf.as_bytes_mut().copy_from_slice(data);
+        if let Err(err) = self.fs_write(
+            FileSystemRequest::new(),
+            file,
+            &[buf],
+            offset,
+            move |req: FileSystemRequest| {
+                on_write.complete(fs_status(req.result()).map(|nwritten| nwritten as usize));
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+            },
+        ) {
+            unsafe { dealloc_base(buf.base(), buf.len()) };
+            completer.complete(Err(err));
+        }
+        completion
+    }
 }
 
 // trait
@@ -450,6 +692,93 @@ impl<'a> From<()> for FileSystemCallback<'a> {
     }
 }
 
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        {
+            let mut state = self.state.borrow_mut();
+            if !state.buffer.is_empty() || buf.is_empty() {
+                return state.buffer.read(buf);
+            }
+
+            if let Some(err) = state.error.take() {
+                return Err(io::Error::from(err));
+            }
+            if state.eof {
+                return Ok(0);
+            }
+            if state.reading {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+        }
+
+        self.read_more().map_err(io::Error::from)?;
+        Err(io::ErrorKind::WouldBlock.into())
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.take_error()?;
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        let buf = Buf::new_with_len(data.len());
+        buf.as_bytes_mut().copy_from_slice(data);
+
+        // NOTE: each write gets its own offset so queued writes land in order on the threadpool
+        let offset = self.state.borrow().write_offset;
+        let state = self.state.clone();
+        let r#loop = self.r#loop;
+        let file = self.file;
+        let result = self.r#loop.fs_write(
+            FileSystemRequest::new(),
+            self.file,
+            &[buf],
+            offset,
+            move |req: FileSystemRequest| {
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+                let mut state = state.borrow_mut();
+                state.pending -= buf.len();
+                if let Err(err) = fs_status(req.result()) {
+                    state.error.get_or_insert(err);
+                }
+                state.close_if_idle(r#loop, file);
+            },
+        );
+
+        match result {
+            Ok(()) => {
+                let mut state = self.state.borrow_mut();
+                state.pending += data.len();
+                state.write_offset += data.len() as i64;
+                Ok(data.len())
+            }
+            Err(err) => {
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+                Err(io::Error::from(err))
+            }
+        }
+    }
+
+    // NOTE: writes are queued on the loop, flush only surfaces failures reported so far
+    fn flush(&mut self) -> io::Result<()> {
+        self.take_error()
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        if self.file < 0 {
+            return;
+        }
+
+        let mut state = self.state.borrow_mut();
+        state.closing = true;
+        state.close_if_idle(self.r#loop, self.file);
+    }
+}
+
 // inner
 
 impl FromInner<uv_fs_type> for FileSystemRequestType {
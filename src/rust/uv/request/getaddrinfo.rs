@@ -0,0 +1,242 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    ffi::CString,
+    mem::zeroed,
+    net::SocketAddr,
+    os::raw::c_int,
+    ptr::null,
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        AF_UNSPEC, Completion, Errno, IRequest, Loop, UserData, addrinfo, completion,
+        from_sockaddr, uv_freeaddrinfo, uv_getaddrinfo, uv_getaddrinfo_t, uv_req_t,
+    },
+};
+
+// super
+
+impl<'a> super::IRequestContext for GetAddrInfoContext<'a> {
+    fn into_request_context(self) -> super::RequestContext {
+        super::RequestContext::from(self)
+    }
+}
+
+impl<'a> super::IRequest for GetAddrInfoRequest {
+    fn into_request(self) -> super::Request {
+        super::Request::from_inner(self.raw as *mut uv_req_t)
+    }
+
+    fn drop_request(self) {
+        let layout = Layout::new::<uv_getaddrinfo_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+// type
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddrInfoHints {
+    pub family: i32,
+    pub socktype: i32,
+    pub protocol: i32,
+    pub flags: i32,
+}
+
+pub struct GetAddrInfoCallback<'a>(
+    pub Box<dyn FnMut(GetAddrInfoRequest, Result<Vec<SocketAddr>, Errno>) + 'a>,
+);
+
+#[derive(Default)]
+#[repr(C)]
+pub struct GetAddrInfoContext<'a> {
+    data: Option<UserData>,
+    getaddrinfo_cb: Option<GetAddrInfoCallback<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GetAddrInfoRequest {
+    raw: *mut uv_getaddrinfo_t,
+}
+
+// fn
+
+pub(crate) unsafe extern "C" fn uv_getaddrinfo_cb(
+    req: *mut uv_getaddrinfo_t,
+    status: c_int,
+    res: *mut addrinfo,
+) {
+    let getaddrinfo = GetAddrInfoRequest::from_inner(req);
+    if let Some(context) = getaddrinfo
+        .into_request()
+        .get_context::<GetAddrInfoContext>()
+    {
+        let status = if status < 0 {
+            Err(Errno::from_inner(status))
+        } else {
+            // unspecified socktypes list every address once per socktype
+            let mut addrs = Vec::new();
+            let mut info = res;
+            while !info.is_null() {
+                if let Some(addr) = from_sockaddr((*info).ai_addr) {
+                    if !addrs.contains(&addr) {
+                        addrs.push(addr);
+                    }
+                }
+                info = (*info).ai_next;
+            }
+            Ok(addrs)
+        };
+
+        if let Some(ref mut getaddrinfo_cb) = context.getaddrinfo_cb {
+            getaddrinfo_cb.0(getaddrinfo, status);
+        }
+    }
+    uv_freeaddrinfo(res);
+    getaddrinfo.into_request().drop_context();
+    getaddrinfo.drop_request();
+}
+
+// impl
+
+impl GetAddrInfoRequest {
+    pub fn new() -> Self {
+        let layout = Layout::new::<uv_getaddrinfo_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_getaddrinfo_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_request(raw as *mut uv_req_t, super::RequestType::GETADDRINFO);
+
+        Self { raw }
+    }
+}
+
+impl Loop {
+    pub fn getaddrinfo<'a, GCB>(
+        &self,
+        req: GetAddrInfoRequest,
+        node: &str,
+        service: Option<&str>,
+        hints: Option<AddrInfoHints>,
+        getaddrinfo_cb: GCB,
+    ) -> Result<(), Errno>
+    where
+        GCB: Into<GetAddrInfoCallback<'a>>,
+    {
+        let node = CString::new(node).map_err(|_| Errno::EINVAL)?;
+        let service = match service {
+            Some(service) => Some(CString::new(service).map_err(|_| Errno::EINVAL)?),
+            None => None,
+        };
+        let hints = hints.map(|hints| {
+            let mut info: addrinfo = unsafe { zeroed() };
+            info.ai_family = hints.family;
+            info.ai_socktype = hints.socktype;
+            info.ai_protocol = hints.protocol;
+            info.ai_flags = hints.flags;
+            info
+        });
+
+        let mut request = req.into_request();
+        match unsafe { request.get_context::<GetAddrInfoContext>() } {
+            Some(context) => {
+                context.getaddrinfo_cb = Some(getaddrinfo_cb.into());
+            }
+            None => request.set_context(GetAddrInfoContext {
+                data: None,
+                getaddrinfo_cb: Some(getaddrinfo_cb.into()),
+            }),
+        };
+
+        // NOTE: libuv copies node, service and hints before returning
+        result!(unsafe {
+            uv_getaddrinfo(
+                self.into_inner(),
+                req.into_inner(),
+                Some(uv_getaddrinfo_cb),
+                node.as_ptr(),
+                service.as_ref().map_or(null(), |service| service.as_ptr()),
+                hints
+                    .as_ref()
+                    .map_or(null(), |hints| hints as *const addrinfo),
+            )
+        })
+    }
+
+    pub fn getaddrinfo_async(
+        &self,
+        node: &str,
+        service: Option<&str>,
+        hints: Option<AddrInfoHints>,
+    ) -> Completion<Result<Vec<SocketAddr>, Errno>> {
+        let (completer, completion) = completion();
+        let on_resolve = completer.clone();
+        let req = GetAddrInfoRequest::new();
+        if let Err(err) = self.getaddrinfo(
+            req,
+            node,
+            service,
+            hints,
+            move |_: GetAddrInfoRequest, addrs: Result<Vec<SocketAddr>, Errno>| {
+                on_resolve.complete(addrs)
+            },
+        ) {
+            req.into_request().drop_context();
+            req.drop_request();
+            completer.complete(Err(err));
+        }
+        completion
+    }
+}
+
+// trait
+
+impl Default for AddrInfoHints {
+    fn default() -> Self {
+        Self {
+            family: AF_UNSPEC as i32,
+            socktype: 0,
+            protocol: 0,
+            flags: 0,
+        }
+    }
+}
+
+impl<'a> From<GetAddrInfoContext<'a>> for super::RequestContext {
+    fn from(value: GetAddrInfoContext<'a>) -> Self {
+        Self { data: value.data }
+    }
+}
+
+impl<'a, Fn> From<Fn> for GetAddrInfoCallback<'a>
+where
+    Fn: FnMut(GetAddrInfoRequest, Result<Vec<SocketAddr>, Errno>) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for GetAddrInfoCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_getaddrinfo_t> for GetAddrInfoRequest {
+    fn from_inner(raw: *mut uv_getaddrinfo_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_getaddrinfo_t> for GetAddrInfoRequest {
+    fn into_inner(self) -> *mut uv_getaddrinfo_t {
+        self.raw
+    }
+}
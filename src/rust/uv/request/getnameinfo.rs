@@ -0,0 +1,193 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    ffi::CStr,
+    net::SocketAddr,
+    os::raw::{c_char, c_int},
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        Completion, Errno, IRequest, Loop, UserData, completion, sockaddr, to_sockaddr,
+        uv_getnameinfo, uv_getnameinfo_t, uv_req_t,
+    },
+};
+
+// super
+
+impl<'a> super::IRequestContext for GetNameInfoContext<'a> {
+    fn into_request_context(self) -> super::RequestContext {
+        super::RequestContext::from(self)
+    }
+}
+
+impl<'a> super::IRequest for GetNameInfoRequest {
+    fn into_request(self) -> super::Request {
+        super::Request::from_inner(self.raw as *mut uv_req_t)
+    }
+
+    fn drop_request(self) {
+        let layout = Layout::new::<uv_getnameinfo_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+// type
+
+pub struct GetNameInfoCallback<'a>(
+    pub Box<dyn FnMut(GetNameInfoRequest, Result<(String, String), Errno>) + 'a>,
+);
+
+#[derive(Default)]
+#[repr(C)]
+pub struct GetNameInfoContext<'a> {
+    data: Option<UserData>,
+    getnameinfo_cb: Option<GetNameInfoCallback<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GetNameInfoRequest {
+    raw: *mut uv_getnameinfo_t,
+}
+
+// fn
+
+pub(crate) unsafe extern "C" fn uv_getnameinfo_cb(
+    req: *mut uv_getnameinfo_t,
+    status: c_int,
+    hostname: *const c_char,
+    service: *const c_char,
+) {
+    let getnameinfo = GetNameInfoRequest::from_inner(req);
+    if let Some(context) = getnameinfo
+        .into_request()
+        .get_context::<GetNameInfoContext>()
+    {
+        let status = if status < 0 {
+            Err(Errno::from_inner(status))
+        } else {
+            Ok((
+                CStr::from_ptr(hostname).to_string_lossy().into_owned(),
+                CStr::from_ptr(service).to_string_lossy().into_owned(),
+            ))
+        };
+
+        if let Some(ref mut getnameinfo_cb) = context.getnameinfo_cb {
+            getnameinfo_cb.0(getnameinfo, status);
+        }
+    }
+    getnameinfo.into_request().drop_context();
+    getnameinfo.drop_request();
+}
+
+// impl
+
+impl GetNameInfoRequest {
+    pub fn new() -> Self {
+        let layout = Layout::new::<uv_getnameinfo_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_getnameinfo_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_request(raw as *mut uv_req_t, super::RequestType::GETNAMEINFO);
+
+        Self { raw }
+    }
+}
+
+impl Loop {
+    pub fn getnameinfo<'a, GCB>(
+        &self,
+        req: GetNameInfoRequest,
+        addr: &SocketAddr,
+        flags: i32,
+        getnameinfo_cb: GCB,
+    ) -> Result<(), Errno>
+    where
+        GCB: Into<GetNameInfoCallback<'a>>,
+    {
+        let mut request = req.into_request();
+        match unsafe { request.get_context::<GetNameInfoContext>() } {
+            Some(context) => {
+                context.getnameinfo_cb = Some(getnameinfo_cb.into());
+            }
+            None => request.set_context(GetNameInfoContext {
+                data: None,
+                getnameinfo_cb: Some(getnameinfo_cb.into()),
+            }),
+        };
+
+        let addr = to_sockaddr(addr);
+        result!(unsafe {
+            uv_getnameinfo(
+                self.into_inner(),
+                req.into_inner(),
+                Some(uv_getnameinfo_cb),
+                &addr as *const _ as *const sockaddr,
+                flags,
+            )
+        })
+    }
+
+    pub fn getnameinfo_async(
+        &self,
+        addr: &SocketAddr,
+        flags: i32,
+    ) -> Completion<Result<(String, String), Errno>> {
+        let (completer, completion) = completion();
+        let on_resolve = completer.clone();
+        let req = GetNameInfoRequest::new();
+        if let Err(err) = self.getnameinfo(
+            req,
+            addr,
+            flags,
+            move |_: GetNameInfoRequest, names: Result<(String, String), Errno>| {
+                on_resolve.complete(names)
+            },
+        ) {
+            req.into_request().drop_context();
+            req.drop_request();
+            completer.complete(Err(err));
+        }
+        completion
+    }
+}
+
+// trait
+
+impl<'a> From<GetNameInfoContext<'a>> for super::RequestContext {
+    fn from(value: GetNameInfoContext<'a>) -> Self {
+        Self { data: value.data }
+    }
+}
+
+impl<'a, Fn> From<Fn> for GetNameInfoCallback<'a>
+where
+    Fn: FnMut(GetNameInfoRequest, Result<(String, String), Errno>) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for GetNameInfoCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_getnameinfo_t> for GetNameInfoRequest {
+    fn from_inner(raw: *mut uv_getnameinfo_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_getnameinfo_t> for GetNameInfoRequest {
+    fn into_inner(self) -> *mut uv_getnameinfo_t {
+        self.raw
+    }
+}
@@ -3,6 +3,9 @@
 pub(crate) mod write;
 pub(crate) use write::*;
 
+pub(crate) mod connect;
+pub(crate) use connect::*;
+
 pub(crate) mod shutdown;
 pub(crate) use shutdown::*;
 
@@ -12,18 +15,26 @@ pub(crate) use fs::*;
 pub(crate) mod work;
 pub(crate) use work::*;
 
-use std::{
-    any::{Any, TypeId},
-    ffi::CStr,
-    os::raw::c_void,
-    ptr::null_mut,
-};
+pub(crate) mod getaddrinfo;
+pub(crate) use getaddrinfo::*;
+
+pub(crate) mod getnameinfo;
+pub(crate) use getnameinfo::*;
+
+pub(crate) mod random;
+pub(crate) use random::*;
+
+pub(crate) mod typed;
+pub(crate) use typed::*;
+
+use std::{ffi::CStr, os::raw::c_void, ptr::null_mut};
 
 use crate::{
     inners::{FromInner, IntoInner},
     uv::{
-        self, Errno, uv_cancel, uv_fs_t, uv_req_get_data, uv_req_get_type, uv_req_set_data,
-        uv_req_t, uv_req_type, uv_req_type_name, uv_shutdown_t, uv_work_t, uv_write_t,
+        self, Errno, UserData, uv_cancel, uv_connect_t, uv_fs_t, uv_getaddrinfo_t,
+        uv_getnameinfo_t, uv_random_t, uv_req_get_data, uv_req_get_type, uv_req_set_data, uv_req_t,
+        uv_req_type, uv_req_type_name, uv_shutdown_t, uv_work_t, uv_write_t,
     },
 };
 
@@ -46,9 +57,10 @@ pub enum RequestType {
     REQ_TYPE_MAX,
 }
 
+#[derive(Default)]
 #[repr(C)]
 pub struct RequestContext {
-    data: *mut c_void,
+    data: Option<UserData>,
 }
 
 pub trait IRequestContext {
@@ -76,7 +88,9 @@ pub trait IRequest: Copy {
 
 // fn
 
-pub(crate) fn init_request(raw: *mut uv_req_t) {
+// NOTE: libuv only sets the type on submit, contexts created before that need to know it
+pub(crate) fn init_request(raw: *mut uv_req_t, r#type: RequestType) {
+    unsafe { (*raw).type_ = r#type.into_inner() };
     unsafe { uv_req_set_data(raw, null_mut()) };
 }
 
@@ -113,29 +127,30 @@ impl Request {
         RequestType::from_inner(unsafe { uv_req_get_type(self.raw) })
     }
 
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        if let Some(context) = unsafe { self.get_context::<RequestContext>() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "Request::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
+    pub(crate) fn user_data(&self) -> Option<&UserData> {
+        let context = unsafe { uv_req_get_data(self.raw) } as *const RequestContext;
+        if context.is_null() {
+            None
         } else {
+            unsafe { (*context).data.as_ref() }
+        }
+    }
+
+    pub(crate) fn user_data_mut(&mut self) -> Option<&mut UserData> {
+        let context = unsafe { uv_req_get_data(self.raw) } as *mut RequestContext;
+        if context.is_null() {
             None
+        } else {
+            unsafe { (*context).data.as_mut() }
         }
     }
 
-    pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
-        match unsafe { self.get_context::<RequestContext>() } {
-            Some(context) => context.data = data,
-            None => {
-                self.set_context(RequestContext { data });
-            }
+    pub(crate) fn set_user_data(&mut self, data: Option<UserData>) {
+        if unsafe { self.get_context::<RequestContext>() }.is_none() {
+            self.init_context();
+        }
+        if let Some(context) = unsafe { self.get_context::<RequestContext>() } {
+            context.data = data;
         }
     }
 
@@ -152,10 +167,40 @@ impl Request {
         unsafe { uv_req_set_data(self.raw, Box::into_raw(Box::new(context)) as *mut c_void) };
     }
 
+    // NOTE: every request type owns exactly one context type, created and dropped as that type
+    pub(crate) fn init_context(&mut self) {
+        match self.get_type() {
+            RequestType::WRITE => self.set_context(WriteContext::default()),
+            RequestType::CONNECT => self.set_context(ConnectContext::default()),
+            RequestType::SHUTDOWN => self.set_context(ShutdownContext::default()),
+            RequestType::FS => self.set_context(FileSystemContext::default()),
+            RequestType::WORK => self.set_context(WorkContext::default()),
+            RequestType::GETADDRINFO => self.set_context(GetAddrInfoContext::default()),
+            RequestType::GETNAMEINFO => self.set_context(GetNameInfoContext::default()),
+            RequestType::RANDOM => self.set_context(RandomContext::default()),
+            _ => self.set_context(RequestContext::default()),
+        }
+    }
+
     pub(crate) fn drop_context(&mut self) {
         let context = unsafe { uv_req_get_data(self.raw) };
-        if !context.is_null() {
-            drop(unsafe { Box::from_raw(context) })
+        if context.is_null() {
+            return;
+        }
+
+        unsafe {
+            match self.get_type() {
+                RequestType::WRITE => drop(Box::from_raw(context as *mut WriteContext)),
+                RequestType::CONNECT => drop(Box::from_raw(context as *mut ConnectContext)),
+                RequestType::SHUTDOWN => drop(Box::from_raw(context as *mut ShutdownContext)),
+                RequestType::FS => drop(Box::from_raw(context as *mut FileSystemContext)),
+                RequestType::WORK => drop(Box::from_raw(context as *mut WorkContext)),
+                RequestType::GETADDRINFO => drop(Box::from_raw(context as *mut GetAddrInfoContext)),
+                RequestType::GETNAMEINFO => drop(Box::from_raw(context as *mut GetNameInfoContext)),
+                RequestType::RANDOM => drop(Box::from_raw(context as *mut RandomContext)),
+                _ => drop(Box::from_raw(context as *mut RequestContext)),
+            }
+            uv_req_set_data(self.raw, null_mut());
         }
     }
 }
@@ -170,6 +215,9 @@ impl IRequest for Request {
             RequestType::WRITE => {
                 WriteRequest::from_inner(self.raw as *mut uv_write_t).drop_request()
             }
+            RequestType::CONNECT => {
+                ConnectRequest::from_inner(self.raw as *mut uv_connect_t).drop_request()
+            }
             RequestType::SHUTDOWN => {
                 ShutdownRequest::from_inner(self.raw as *mut uv_shutdown_t).drop_request()
             }
@@ -177,6 +225,15 @@ impl IRequest for Request {
                 FileSystemRequest::from_inner(self.raw as *mut uv_fs_t).drop_request()
             }
             RequestType::WORK => WorkRequest::from_inner(self.raw as *mut uv_work_t).drop_request(),
+            RequestType::GETADDRINFO => {
+                GetAddrInfoRequest::from_inner(self.raw as *mut uv_getaddrinfo_t).drop_request()
+            }
+            RequestType::GETNAMEINFO => {
+                GetNameInfoRequest::from_inner(self.raw as *mut uv_getnameinfo_t).drop_request()
+            }
+            RequestType::RANDOM => {
+                RandomRequest::from_inner(self.raw as *mut uv_random_t).drop_request()
+            }
             _ => panic!(
                 "Request::drop_request: unexpected type [{}]",
                 self.get_type().name()
@@ -0,0 +1,206 @@
+use std::{
+    alloc::{Layout, alloc, dealloc},
+    os::raw::{c_int, c_void},
+    ptr::null_mut,
+};
+
+use crate::{
+    inners::{FromInner, IntoInner},
+    result,
+    uv::{
+        Buf, Completion, Errno, IRequest, Loop, UserData, completion, dealloc_base, uv_random,
+        uv_random_t, uv_req_t,
+    },
+};
+
+// super
+
+impl<'a> super::IRequestContext for RandomContext<'a> {
+    fn into_request_context(self) -> super::RequestContext {
+        super::RequestContext::from(self)
+    }
+}
+
+impl<'a> super::IRequest for RandomRequest {
+    fn into_request(self) -> super::Request {
+        super::Request::from_inner(self.raw as *mut uv_req_t)
+    }
+
+    fn drop_request(self) {
+        let layout = Layout::new::<uv_random_t>();
+        unsafe { dealloc(self.raw as *mut u8, layout) };
+    }
+}
+
+// type
+
+pub struct RandomCallback<'a>(pub Box<dyn FnMut(RandomRequest, Result<Buf, Errno>) + 'a>);
+
+#[derive(Default)]
+#[repr(C)]
+pub struct RandomContext<'a> {
+    data: Option<UserData>,
+    random_cb: Option<RandomCallback<'a>>,
+    buf: Option<Buf>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RandomRequest {
+    raw: *mut uv_random_t,
+}
+
+// fn
+
+pub(crate) unsafe extern "C" fn uv_random_cb(
+    req: *mut uv_random_t,
+    status: c_int,
+    _: *mut c_void,
+    _: usize,
+) {
+    let random = RandomRequest::from_inner(req);
+    if let Some(context) = random.into_request().get_context::<RandomContext>() {
+        let status = if status < 0 {
+            Err(Errno::from_inner(status))
+        } else {
+            context.buf.take().ok_or(Errno::EINVAL)
+        };
+
+        if let Some(ref mut random_cb) = context.random_cb {
+            random_cb.0(random, status);
+        }
+    }
+    random.into_request().drop_context();
+    random.drop_request();
+}
+
+// impl
+
+impl RandomRequest {
+    pub fn new() -> Self {
+        let layout = Layout::new::<uv_random_t>();
+        let raw = unsafe { alloc(layout) as *mut uv_random_t };
+        if raw.is_null() {
+            panic!("{}", Errno::ENOMEM);
+        }
+
+        super::init_request(raw as *mut uv_req_t, super::RequestType::RANDOM);
+
+        Self { raw }
+    }
+}
+
+impl Loop {
+    pub fn random<'a, RCB>(
+        &self,
+        req: RandomRequest,
+        buf: Buf,
+        flags: u32,
+        random_cb: RCB,
+    ) -> Result<(), Errno>
+    where
+        RCB: Into<RandomCallback<'a>>,
+    {
+        let mut request = req.into_request();
+        match unsafe { request.get_context::<RandomContext>() } {
+            Some(context) => {
+                context.random_cb = Some(random_cb.into());
+                context.buf = Some(buf);
+            }
+            None => request.set_context(RandomContext {
+                data: None,
+                random_cb: Some(random_cb.into()),
+                buf: Some(buf),
+            }),
+        };
+
+        result!(unsafe {
+            uv_random(
+                self.into_inner(),
+                req.into_inner(),
+                buf.base() as *mut c_void,
+                buf.len(),
+                flags,
+                Some(uv_random_cb),
+            )
+        })
+    }
+
+    pub fn random_sync(&self, buf: Buf, flags: u32) -> Result<Buf, Errno> {
+        let result = unsafe {
+            uv_random(
+                null_mut(),
+                null_mut(),
+                buf.base() as *mut c_void,
+                buf.len(),
+                flags,
+                None,
+            )
+        };
+
+        if result < 0 {
+            Err(Errno::from_inner(result))
+        } else {
+            Ok(buf)
+        }
+    }
+
+    pub fn random_async(&self, len: usize) -> Completion<Result<Vec<u8>, Errno>> {
+        let (completer, completion) = completion();
+        let on_random = completer.clone();
+        let req = RandomRequest::new();
+        let buf = Buf::new_with_len(len);
+        if let Err(err) = self.random(
+            req,
+            buf,
+            0,
+            move |_: RandomRequest, result: Result<Buf, Errno>| {
+                on_random.complete(result.map(|buf| buf.as_bytes().to_vec()));
+                unsafe { dealloc_base(buf.base(), buf.len()) };
+            },
+        ) {
+            // the callback never runs, the request and the buffer are still ours
+            req.into_request().drop_context();
+            req.drop_request();
+            unsafe { dealloc_base(buf.base(), buf.len()) };
+            completer.complete(Err(err));
+        }
+        completion
+    }
+}
+
+// trait
+
+impl<'a> From<RandomContext<'a>> for super::RequestContext {
+    fn from(value: RandomContext<'a>) -> Self {
+        Self { data: value.data }
+    }
+}
+
+impl<'a, Fn> From<Fn> for RandomCallback<'a>
+where
+    Fn: FnMut(RandomRequest, Result<Buf, Errno>) + 'a,
+{
+    fn from(value: Fn) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<'a> From<()> for RandomCallback<'a> {
+    fn from(_: ()) -> Self {
+        Self(Box::new(|_, _| ()))
+    }
+}
+
+// inner
+
+impl FromInner<*mut uv_random_t> for RandomRequest {
+    fn from_inner(raw: *mut uv_random_t) -> Self {
+        Self { raw }
+    }
+}
+
+impl IntoInner<*mut uv_random_t> for RandomRequest {
+    fn into_inner(self) -> *mut uv_random_t {
+        self.raw
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    any::type_name,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::uv::{Errno, IRequest, Request, UserData};
+
+// type
+
+// NOTE: neither Clone nor Copy, the data is only reachable through the one TypedRequest owning it.
+// Deref gives the data too, a copy of the request could be cancelled behind the wrapper's back
+#[derive(Debug)]
+pub struct TypedRequest<T: IRequest, D: 'static> {
+    request: T,
+    inner: Request,
+    data: PhantomData<D>,
+}
+
+// impl
+
+impl<T: IRequest, D: 'static> TypedRequest<T, D> {
+    pub fn new(request: T, data: D) -> Self {
+        let mut inner = request.into_request();
+        inner.set_user_data(Some(UserData::new(data)));
+        Self {
+            request,
+            inner,
+            data: PhantomData,
+        }
+    }
+
+    // NOTE: the caller makes sure no other TypedRequest wraps the same request
+    pub unsafe fn from_request(request: T) -> Option<Self> {
+        let inner = request.into_request();
+        if !inner.user_data().is_some_and(|data| data.is::<D>()) {
+            return None;
+        }
+
+        Some(Self {
+            request,
+            inner,
+            data: PhantomData,
+        })
+    }
+
+    pub fn request(&self) -> T {
+        self.request
+    }
+
+    pub fn data(&self) -> &D {
+        self.inner
+            .user_data()
+            .and_then(|data| data.get::<D>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "TypedRequest::data: [{}] no longer holds [{}]",
+                    self.request.get_type().name(),
+                    type_name::<D>()
+                )
+            })
+    }
+
+    pub fn data_mut(&mut self) -> &mut D {
+        let name = self.request.get_type().name();
+        self.inner
+            .user_data_mut()
+            .and_then(|data| data.get_mut::<D>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "TypedRequest::data_mut: [{}] no longer holds [{}]",
+                    name,
+                    type_name::<D>()
+                )
+            })
+    }
+
+    pub fn replace(&mut self, data: D) {
+        *self.data_mut() = data;
+    }
+
+    pub fn cancel(self) -> Result<(), Errno> {
+        self.inner.cancel()
+    }
+}
+
+// trait
+
+impl<T: IRequest, D: 'static> Deref for TypedRequest<T, D> {
+    type Target = D;
+
+    fn deref(&self) -> &Self::Target {
+        self.data()
+    }
+}
+
+impl<T: IRequest, D: 'static> DerefMut for TypedRequest<T, D> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.data_mut()
+    }
+}
@@ -1,14 +1,12 @@
 use std::{
     alloc::{Layout, alloc, dealloc},
-    any::{Any, TypeId},
-    os::raw::{c_int, c_void},
-    ptr::null_mut,
+    os::raw::c_int,
 };
 
 use crate::{
     inners::{FromInner, IntoInner},
     result,
-    uv::{Errno, IRequest, Loop, uv_queue_work, uv_req_t, uv_work_t},
+    uv::{Errno, IRequest, Loop, UserData, mark_started, uv_queue_work, uv_req_t, uv_work_t},
 };
 
 // super
@@ -35,9 +33,10 @@ impl<'a> super::IRequest for WorkRequest {
 pub struct WorkCallback<'a>(pub Box<dyn FnMut(&'a mut WorkRequest) + 'a>);
 pub struct AfterWorkCallback<'a>(pub Box<dyn FnMut(WorkRequest, Result<(), Errno>) + 'a>);
 
+#[derive(Default)]
 #[repr(C)]
 pub struct WorkContext<'a> {
-    data: *mut c_void,
+    data: Option<UserData>,
     work_cb: Option<WorkCallback<'a>>,
     after_work_cb: Option<AfterWorkCallback<'a>>,
 }
@@ -85,42 +84,10 @@ impl WorkRequest {
             panic!("{}", Errno::ENOMEM);
         }
 
-        super::init_request(raw as *mut uv_req_t);
+        super::init_request(raw as *mut uv_req_t, super::RequestType::WORK);
 
         Self { raw }
     }
-
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        let request = self.into_request();
-        if let Some(context) = unsafe { request.get_context::<WorkContext>() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "WorkRequest::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
-        } else {
-            None
-        }
-    }
-
-    pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
-        let mut request = self.into_request();
-        match unsafe { request.get_context::<WorkContext>() } {
-            Some(context) => context.data = data,
-            None => {
-                request.set_context(WorkContext {
-                    data,
-                    work_cb: None,
-                    after_work_cb: None,
-                });
-            }
-        }
-    }
 }
 
 impl Loop {
@@ -142,7 +109,7 @@ impl Loop {
             }
             None => {
                 let new_context = WorkContext {
-                    data: null_mut(),
+                    data: None,
                     work_cb: Some(work_cb.into()),
                     after_work_cb: Some(after_work_cb.into()),
                 };
@@ -150,6 +117,7 @@ impl Loop {
             }
         };
 
+        mark_started();
         result!(unsafe {
             uv_queue_work(
                 self.into_inner(),
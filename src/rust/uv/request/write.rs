@@ -1,12 +1,11 @@
 use std::{
     alloc::{Layout, alloc, dealloc},
-    any::{Any, TypeId},
-    os::raw::{c_int, c_void},
+    os::raw::c_int,
 };
 
 use crate::{
     inners::{FromInner, IntoInner},
-    uv::{Errno, IRequest, uv_req_t, uv_write_t},
+    uv::{Errno, IRequest, UserData, uv_req_t, uv_write_t},
 };
 
 // super
@@ -32,9 +31,10 @@ impl<'a> super::IRequest for WriteRequest {
 
 pub struct WriteCallback<'a>(pub Box<dyn FnMut(WriteRequest, Result<(), Errno>) + 'a>);
 
+#[derive(Default)]
 #[repr(C)]
 pub struct WriteContext<'a> {
-    pub(crate) data: *mut c_void,
+    pub(crate) data: Option<UserData>,
     pub(crate) write_cb: Option<WriteCallback<'a>>,
 }
 
@@ -72,40 +72,10 @@ impl WriteRequest {
             panic!("{}", Errno::ENOMEM);
         }
 
-        super::init_request(raw as *mut uv_req_t);
+        super::init_request(raw as *mut uv_req_t, super::RequestType::WRITE);
 
         Self { raw }
     }
-    pub fn get_data<D: 'static>(&self) -> Option<&mut D> {
-        let request = self.into_request();
-        if let Some(context) = unsafe { request.get_context::<WriteContext>() } {
-            Some(unsafe {
-                (*(context.data as *mut dyn Any))
-                    .downcast_mut::<D>()
-                    .expect(&format!(
-                        "WriteRequest::get_data: unexpected type, expected: [{:?}] but was: [{:?}]",
-                        TypeId::of::<D>(),
-                        (*context.data).type_id()
-                    ))
-            })
-        } else {
-            None
-        }
-    }
-
-    pub fn set_data<D: 'static>(&mut self, data: D) {
-        let data = Box::into_raw(Box::new(data)) as *mut c_void;
-        let mut request = self.into_request();
-        match unsafe { request.get_context::<WriteContext>() } {
-            Some(context) => context.data = data,
-            None => {
-                request.set_context(WriteContext {
-                    data,
-                    write_cb: None,
-                });
-            }
-        }
-    }
 }
 
 // trait
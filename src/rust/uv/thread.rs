@@ -0,0 +1,262 @@
+use std::{
+    collections::VecDeque,
+    hash::{DefaultHasher, Hash, Hasher},
+    mem::take,
+    panic::resume_unwind,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{Receiver, Sender, channel},
+    },
+    thread::{Builder, JoinHandle},
+};
+
+use crate::uv::{AsyncHandle, AsyncSender, Errno, IHandle, Loop, RunMode};
+
+// type
+
+type RemoteTask = Box<dyn FnOnce(&mut Loop) + Send>;
+
+struct RemoteState {
+    tasks: VecDeque<RemoteTask>,
+    sender: Option<AsyncSender>,
+    shutdown: bool,
+}
+
+#[derive(Clone)]
+pub struct LoopRemote {
+    state: Arc<Mutex<RemoteState>>,
+}
+
+pub struct LoopThread {
+    remote: LoopRemote,
+    thread: Option<JoinHandle<Result<(), Errno>>>,
+}
+
+pub struct LoopPool {
+    threads: Vec<LoopThread>,
+    next: AtomicUsize,
+}
+
+// fn
+
+fn drain_remote(handle: &AsyncHandle, state: &Mutex<RemoteState>) {
+    let (tasks, shutdown) = {
+        let mut state = state.lock().unwrap();
+        (take(&mut state.tasks), state.shutdown)
+    };
+
+    let mut r#loop = handle.get_loop();
+    for task in tasks {
+        task(&mut r#loop);
+    }
+
+    if shutdown {
+        // taken under the lock so no remote can send into a closing handle
+        state.lock().unwrap().sender.take();
+        r#loop.walk(|mut handle| {
+            if !handle.closing() {
+                handle.close(());
+            }
+        });
+    }
+}
+
+fn run_loop_thread(
+    state: Arc<Mutex<RemoteState>>,
+    ready: Sender<Result<(), Errno>>,
+) -> Result<(), Errno> {
+    let mut r#loop = match Loop::new() {
+        Ok(r#loop) => r#loop,
+        Err(err) => {
+            let _ = ready.send(Err(err));
+            return Err(err);
+        }
+    };
+
+    let wakeup = r#loop.new_async({
+        let state = state.clone();
+        move |handle: &AsyncHandle| drain_remote(handle, &state)
+    });
+    match wakeup {
+        Ok(wakeup) => {
+            state.lock().unwrap().sender = Some(wakeup.sender());
+            let _ = ready.send(Ok(()));
+        }
+        Err(err) => {
+            let _ = ready.send(Err(err));
+            r#loop.close()?;
+            return Err(err);
+        }
+    }
+
+    // the wakeup handle keeps the loop alive until shutdown closes every handle
+    while r#loop.alive() {
+        r#loop.run(RunMode::DEFAULT)?;
+    }
+
+    r#loop.close()
+}
+
+// impl
+
+impl LoopRemote {
+    pub fn submit<F, R>(&self, task: F) -> Result<Receiver<R>, Errno>
+    where
+        F: FnOnce(&mut Loop) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (result_tx, result_rx) = channel();
+        let mut state = self.state.lock().unwrap();
+        match state.sender {
+            Some(sender) if !state.shutdown => {
+                state.tasks.push_back(Box::new(move |r#loop: &mut Loop| {
+                    let _ = result_tx.send(task(r#loop));
+                }));
+                sender.send()?;
+                Ok(result_rx)
+            }
+            _ => Err(Errno::ECANCELED),
+        }
+    }
+
+    // NOTE: blocks the caller, never call it from the loop thread itself
+    pub fn call<F, R>(&self, task: F) -> Result<R, Errno>
+    where
+        F: FnOnce(&mut Loop) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.submit(task)?.recv().map_err(|_| Errno::ECANCELED)
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.shutdown || state.sender.is_none()
+    }
+}
+
+impl LoopThread {
+    pub fn spawn() -> Result<Self, Errno> {
+        Self::spawn_named("uv-loop")
+    }
+
+    pub fn spawn_named(name: &str) -> Result<Self, Errno> {
+        let state = Arc::new(Mutex::new(RemoteState {
+            tasks: VecDeque::new(),
+            sender: None,
+            shutdown: false,
+        }));
+
+        let (ready_tx, ready_rx) = channel();
+        let thread = Builder::new()
+            .name(name.to_string())
+            .spawn({
+                let state = state.clone();
+                move || run_loop_thread(state, ready_tx)
+            })
+            .expect("LoopThread::spawn: failed to spawn the loop thread");
+
+        ready_rx.recv().map_err(|_| Errno::ECANCELED)??;
+
+        Ok(Self {
+            remote: LoopRemote { state },
+            thread: Some(thread),
+        })
+    }
+
+    pub fn remote(&self) -> LoopRemote {
+        self.remote.clone()
+    }
+
+    pub fn shutdown(mut self) -> Result<(), Errno> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Result<(), Errno> {
+        {
+            let mut state = self.remote.state.lock().unwrap();
+            if let Some(sender) = state.sender {
+                state.shutdown = true;
+                sender.send()?;
+            }
+        }
+
+        match self.thread.take() {
+            Some(thread) => match thread.join() {
+                Ok(result) => result,
+                Err(panic) => resume_unwind(panic),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+impl LoopPool {
+    pub fn new(size: usize) -> Result<Self, Errno> {
+        if size == 0 {
+            return Err(Errno::EINVAL);
+        }
+
+        let threads = (0..size)
+            .map(|index| LoopThread::spawn_named(&format!("uv-loop-{}", index)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            threads,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.threads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.threads.is_empty()
+    }
+
+    pub fn remote(&self, index: usize) -> Option<LoopRemote> {
+        self.threads.get(index).map(|thread| thread.remote())
+    }
+
+    pub fn next(&self) -> LoopRemote {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.threads.len();
+        self.threads[index].remote()
+    }
+
+    // NOTE: the same key always lands on the same loop, so its handles never cross threads
+    pub fn route<K: Hash>(&self, key: &K) -> LoopRemote {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() % self.threads.len() as u64) as usize;
+        self.threads[index].remote()
+    }
+
+    pub fn submit<F, R>(&self, task: F) -> Result<Receiver<R>, Errno>
+    where
+        F: FnOnce(&mut Loop) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.next().submit(task)
+    }
+
+    pub fn shutdown(self) -> Result<(), Errno> {
+        // every loop is shut down even if an earlier one failed, the first error wins
+        let mut result = Ok(());
+        for thread in self.threads {
+            let shutdown = thread.shutdown();
+            if result.is_ok() {
+                result = shutdown;
+            }
+        }
+        result
+    }
+}
+
+// trait
+
+impl Drop for LoopThread {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}